@@ -1,8 +1,27 @@
+use crate::cli::LaunchArgs;
+use crate::config::Config;
 use crate::state::State;
+use crate::state::binding_editor::BindingEditorActions;
+use crate::state::count::CountActions;
+use crate::state::ipc::IpcActions;
 use crate::state::keymap::resolve_keybind;
+use crate::state::macros::MacroActions;
+use crate::state::notification::NotificationActions;
+use crate::state::overview::OverviewActions;
+use crate::state::pointer::PointerActions;
+use crate::state::screen::{ScreenActions, TransientTool};
+use crate::state::script::ScriptActions;
+use crate::state::status_bar::StatusBarActions;
+use crate::state::template::TemplateActions;
+use crate::state::theme::ThemeActions;
+use crate::state::trigger::{HOLD_CHECK_INTERVAL, TriggerActions};
+use crate::state::workspace::WorkspaceActions;
 use crate::{message::Message, state::command::CommandActions};
-use iced::{Subscription, Task, Theme, keyboard};
+use anyhow::Result;
+use iced::{Subscription, Task, Theme, keyboard, mouse, window};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use time::OffsetDateTime;
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -10,27 +29,119 @@ pub enum AppError {
     CommandNotFound { handle: String },
 }
 
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
 pub type App = State;
 
 impl App {
-    pub fn new() -> (Self, Task<Message>)
+    pub fn new(config: Config, launch: LaunchArgs) -> (Self, Task<Message>) {
+        let mut state = Self::load(config);
+
+        let window_task = state.open_initial_screen();
+        state.create_startup_workspaces();
+        let result = state.launch(launch);
+        let launch_task = state.report(result);
+        let task = Task::batch([
+            window_task,
+            state.detect_system_theme(),
+            state.refresh_status_bar(),
+            launch_task,
+        ]);
+
+        (state, task)
+    }
+
+    // State with every command registered, before any window or workspace exists.
+    pub fn load(config: Config) -> Self
     where
         Self: CommandActions,
     {
-        let mut state = State::default();
+        let scripts = config.scripts.clone();
+        let macros = config.macros.clone();
+        let composites = config.commands.clone();
+        let mut state = State::from_config(config);
 
         state.merge_commands(crate::state::command::global_commands());
         state.merge_commands(crate::state::workspace::workspace_commands());
         state.merge_commands(crate::state::view::view_commands());
+        state.merge_commands(crate::state::notification::notification_commands());
+        state.merge_commands(crate::state::theme::theme_commands());
+        state.merge_commands(crate::state::ui::ui_commands());
+        state.merge_commands(crate::state::screen::screen_commands());
+        state.merge_commands(crate::state::picker::picker_commands());
+        state.merge_commands(crate::state::template::template_commands());
+        state.merge_commands(crate::state::macros::macro_commands());
+        state.merge_commands(crate::state::keymap::keymap_commands());
+        state.merge_commands(crate::state::binding_editor::binding_editor_commands());
+        state.merge_commands(crate::state::overview::overview_commands());
+        // User defined commands come last so they cannot shadow built-in ones.
+        state.load_macros(macros);
+        state.load_scripts(scripts);
+        state.load_composite_commands(composites);
+
+        state
+    }
+
+    // Applies launch arguments of this or a forwarded invocation.
+    pub fn launch(&mut self, args: LaunchArgs) -> Result<Task<Message>> {
+        let mut tasks = Vec::new();
+
+        if let Some(directory) = args.directory {
+            self.open_workspace(directory)?;
+        }
+
+        for handle in &args.run {
+            tasks.push(self.run_command(handle)?);
+        }
 
-        (state, Task::none())
+        Ok(Task::batch(tasks))
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        let mode = self.mode.clone();
+        let task = self.handle_message(message);
+
+        if self.mode != mode {
+            self.emit_mode_changed();
+        }
+
+        task
+    }
+
+    // Failures are shown as notifications instead of stopping the application.
+    fn report(&mut self, result: Result<Task<Message>>) -> Task<Message> {
+        result.unwrap_or_else(|err| {
+            self.push_error(err);
+            Task::none()
+        })
+    }
+
+    fn handle_message(&mut self, message: Message) -> Task<Message> {
         let message = match message {
             Message::KeyPress { key, modifiers } => {
-                resolve_keybind(self, (key, modifiers)).map(Message::Command)
+                if let Some(result) = self.capture_key_pressed(&key, modifiers) {
+                    return self.report(result);
+                }
+                if let Some(result) = self.register_key_pressed(&key, modifiers) {
+                    return self.report(result);
+                }
+                if self.overview_key_pressed(&key, modifiers)
+                    || self.count_key_pressed(&key, modifiers)
+                    || self.key_held(&key, modifiers, Instant::now())
+                {
+                    return Task::none();
+                }
+
+                let handle = resolve_keybind(self, (key, modifiers));
+                if handle.is_none() {
+                    self.pending_count = None;
+                }
+                handle.map(Message::Command)
             }
+            Message::KeyRelease { key, modifiers } => self
+                .key_released(&key, modifiers, Instant::now())
+                .map(Message::Command),
+            Message::HoldCheck => self.hold_elapsed(Instant::now()).map(Message::Command),
             _ => Some(message),
         };
 
@@ -41,19 +152,59 @@ impl App {
 
         match message {
             Message::Command(handle) => {
-                if let Some(command) = self.resolve_command(&handle) {
-                    self.last_command = Some(command.clone());
-                    command.run(self)
-                } else {
-                    self.push_error(
-                        AppError::CommandNotFound {
-                            handle: handle.clone(),
-                        }
-                        .into(),
-                    );
-
-                    Task::none()
+                let count = self.take_pending_count();
+                let result = self.run_command_with_count(&handle, count);
+                if result.is_ok() {
+                    self.record_command(&handle);
                 }
+                self.report(result)
+            }
+            Message::TilePressed(id) => {
+                let result = self.tile_pressed(id, Instant::now());
+                self.report(result)
+            }
+            Message::TileReleased(id) => {
+                let result = self.tile_released(id);
+                self.report(result)
+            }
+            Message::WheelScrolled(window, delta) => {
+                let result = self.wheel_scrolled(window, delta);
+                self.report(result)
+            }
+            Message::DividerPressed(window, divider) => {
+                self.divider_pressed(window, divider);
+                Task::none()
+            }
+            Message::PointerMoved(window, position) => {
+                self.pointer_moved(window, position);
+                Task::none()
+            }
+            Message::PointerReleased => {
+                self.pointer_released();
+                Task::none()
+            }
+            Message::DividerReleased => {
+                self.divider_released();
+                Task::none()
+            }
+            Message::WindowClosed(window) => self.close_screen(window),
+            Message::WindowFocused(window) => {
+                self.focus_screen_for_window(window);
+                Task::none()
+            }
+            Message::IpcReady(handle) => {
+                self.ipc = Some(handle);
+                Task::none()
+            }
+            Message::Ipc(request) => self.handle_ipc_request(request),
+            Message::Tick => self.refresh_status_bar(),
+            Message::ShellSegmentOutput { command, output } => {
+                self.update_shell_segment(command, output);
+                Task::none()
+            }
+            Message::SystemThemeDetected { dark } => {
+                self.themes.apply_system_theme(dark);
+                Task::none()
             }
             Message::ToggleModal => {
                 // if self.screen.transient_tool_id == None {
@@ -67,14 +218,29 @@ impl App {
         }
     }
 
-    pub fn view(&self) -> iced::Element<Message> {
+    pub fn title(&self, window: window::Id) -> String {
+        match self
+            .screens
+            .iter()
+            .position(|screen| screen.window == Some(window))
+        {
+            Some(index) if self.screens.len() > 1 => format!("Cinnabar [{}]", index + 1),
+            _ => "Cinnabar".to_string(),
+        }
+    }
+
+    pub fn view(&self, window: window::Id) -> iced::Element<'_, Message> {
         use crate::elements::screen;
 
-        screen(self)
+        match self.screen_for_window(window) {
+            Some(window_screen) => screen(self, window, window_screen),
+            // Window is being opened or closed.
+            None => iced::widget::horizontal_space().into(),
+        }
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        keyboard::on_key_press(|key_code, modifiers| {
+        let keys = keyboard::on_key_press(|key_code, modifiers| {
             // Handle key presses in command mode
             match key_code {
                 iced::keyboard::Key::Named(iced::keyboard::key::Named::Tab) => {
@@ -85,10 +251,179 @@ impl App {
                     modifiers,
                 }),
             }
-        })
+        });
+
+        let releases =
+            keyboard::on_key_release(|key, modifiers| Some(Message::KeyRelease { key, modifiers }));
+
+        let keys = Subscription::batch([
+            keys,
+            releases,
+            window::close_events().map(Message::WindowClosed),
+            // Keys go to the focused screen, so follow windows focused by the user.
+            iced::event::listen_with(|event, _status, window| match event {
+                iced::Event::Window(window::Event::Focused) => Some(Message::WindowFocused(window)),
+                _ => None,
+            }),
+            // Divider drag ends even when the button is released away from tiles.
+            iced::event::listen_with(|event, _status, _window| match event {
+                iced::Event::Mouse(
+                    mouse::Event::ButtonReleased(mouse::Button::Left) | mouse::Event::CursorLeft,
+                ) => Some(Message::DividerReleased),
+                _ => None,
+            }),
+            crate::ipc::server::subscription(),
+        ]);
+
+        // Hold binding runs while the key is still down, so it needs its own timer.
+        let keys = if self.waits_for_hold() {
+            Subscription::batch([
+                keys,
+                iced::time::every(HOLD_CHECK_INTERVAL).map(|_| Message::HoldCheck),
+            ])
+        } else {
+            keys
+        };
+
+        // Toasts, log and live status bar segments change on their own,
+        // so keep ticking while any of them is visible.
+        if self.notifications.has_toasts(OffsetDateTime::now_utc())
+            || self
+                .screens
+                .iter()
+                .any(|screen| screen.transient_tool == Some(TransientTool::Log))
+            || self.status_bar.is_live()
+        {
+            Subscription::batch([
+                keys,
+                iced::time::every(TICK_INTERVAL).map(|_| Message::Tick),
+            ])
+        } else {
+            keys
+        }
+    }
+
+    pub fn scale_factor(&self, _window: window::Id) -> f64 {
+        self.ui.zoom
+    }
+
+    pub fn theme(&self, _window: window::Id) -> Theme {
+        self.themes.current().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::binding_editor::binding_editor_commands;
+    use crate::state::command::global_commands;
+    use crate::state::keymap::{KeymapNode, keymap_commands};
+    use crate::state::macros::macro_commands;
+    use crate::state::mode::{Mode, ViewMode};
+    use crate::state::overview::overview_commands;
+    use crate::state::screen::screen_commands;
+    use crate::state::view::view_commands;
+    use crate::state::workspace::workspace_commands;
+    use crate::testing::Harness;
+
+    #[test]
+    fn keys_resolve_through_mode_keymap() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["c", "c", "shift-n"]);
+
+        let first = harness.workspace_ids()[0];
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert_eq!(harness.focused(), Some(first));
+    }
+
+    #[test]
+    fn same_key_runs_different_command_per_mode() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["c", "cmd-v", "n"]);
+
+        assert_eq!(harness.workspace_ids().len(), 1);
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+        assert!(matches!(harness.mode(), Mode::View { .. }));
     }
 
-    pub fn theme(&self) -> Theme {
-        Theme::Light
+    #[test]
+    fn keys_adjust_tiling_in_view_mode() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["cmd-v", "e", "e", "shift-v", "."]);
+
+        let tiling = harness.tiling();
+        assert_eq!(tiling.max_expanded_rows, 4);
+        assert_eq!(tiling.max_columns, 2);
+        assert!(tiling.fullscreen);
+    }
+
+    #[test]
+    fn view_mode_keeps_focused_workspace() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["c", "cmd-v"]);
+
+        let id = harness.workspace_ids().first().copied();
+        assert_eq!(
+            harness.mode(),
+            &Mode::View {
+                mode: ViewMode::Workspace { id }
+            }
+        );
+        assert_eq!(harness.mode_history(), &[Mode::Workspace { id }]);
+
+        harness.press("esc");
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id });
+    }
+
+    #[test]
+    fn unbound_key_is_ignored() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["x", "cmd-shift-x"]);
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+        assert!(harness.app.last_command.is_none());
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn failed_command_is_reported() {
+        let mut harness = Harness::new();
+
+        harness.press("m").run("missing-command");
+
+        assert_eq!(harness.errors(), 2);
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+    }
+
+    #[test]
+    fn every_command_is_bound_in_test_config() {
+        let harness = Harness::new();
+
+        for commands in [
+            global_commands(),
+            workspace_commands(),
+            view_commands(),
+            screen_commands(),
+            macro_commands(),
+            keymap_commands(),
+            binding_editor_commands(),
+            overview_commands(),
+        ] {
+            for command in commands.command_values() {
+                let bound = harness
+                    .app
+                    .keymaps
+                    .values()
+                    .flat_map(|keymap| keymap.mapping.values())
+                    .any(|node| matches!(node, KeymapNode::Command(handle) if handle == command.handle()));
+
+                assert!(bound, "{} is not bound", command.handle());
+            }
+        }
     }
 }
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::filter::LevelFilter;
+
+#[derive(Parser, Debug)]
+#[command(name = "cinnabar", version, about)]
+pub struct Cli {
+    /// Maximum level of logged events (off, error, warn, info, debug, trace)
+    #[arg(long, default_value = "info")]
+    pub log_level: LevelFilter,
+
+    /// Start a separate instance even if one is already running
+    #[arg(long)]
+    pub new_instance: bool,
+
+    #[command(flatten)]
+    pub launch: LaunchArgs,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Arguments applied on launch, forwarded to running instance if there is one
+#[derive(Args, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LaunchArgs {
+    /// Open a workspace rooted at this directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<PathBuf>,
+
+    /// Run a command by its handle after launch, may be repeated
+    #[arg(long = "run", value_name = "HANDLE")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub run: Vec<String>,
+}
+
+/// Commands run instead of launching the application
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    #[command(flatten)]
+    Ipc(IpcCommand),
+    /// Report keymap problems and configuration load errors, without a running instance
+    CheckConfig {
+        /// Configuration file to check instead of the default one
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+}
+
+/// Commands talking to a running instance over the control socket
+#[derive(Subcommand, Debug)]
+pub enum IpcCommand {
+    /// Run a command by its handle, e.g. `workspace-create`
+    Msg {
+        handle: String,
+        /// Count given to commands which accept one, e.g. to move several workspaces
+        #[arg(long)]
+        count: Option<usize>,
+    },
+    /// Print part of application state
+    Query {
+        what: Query,
+        /// Print raw JSON instead of human readable output
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Query {
+    Workspaces,
+    Focused,
+    Mode,
+    Tiling,
+    Commands,
+}
+
+impl Query {
+    pub fn method(self) -> &'static str {
+        match self {
+            Query::Workspaces => "workspaces",
+            Query::Focused => "focused",
+            Query::Mode => "mode",
+            Query::Tiling => "tiling",
+            Query::Commands => "commands",
+        }
+    }
+}
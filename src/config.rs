@@ -1,15 +1,50 @@
-use std::{collections::HashMap, path::PathBuf};
+use anyhow::{Context, Result};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use iced::keyboard::{Key, Modifiers};
+use thiserror::Error;
 use toml::Value;
 
-use crate::state::keymap::{Keymap, KeymapNode, Keymaps};
+use crate::state::command::CompositeConfig;
+use crate::state::keymap::{Keymap, KeymapNode, Keymaps, Trigger};
+use crate::state::macros::is_register;
+use crate::state::status_bar::{
+    DEFAULT_SHELL_INTERVAL, Segment, StatusBarConfig, default_clock_format,
+};
+
+use crate::state::script::{ScriptConfig, ScriptSource};
+use crate::state::template::{ActivityTemplate, StartupConfig, TilingTemplate, WorkspaceTemplate};
+use crate::state::theme::{CustomPalette, ThemeConfig};
+use crate::state::tiling::MAX_WEIGHT;
+use crate::state::ui::{UiConfig, font_with_name};
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("{key:?} in configuration file is not a table")]
+    NotATable { key: String },
+}
 
 pub struct Config {
     pub keymaps: Keymaps,
+    // Keymap name by mode name, for modes not using `<mode>-mode`.
+    pub mode_keymaps: HashMap<String, String>,
+    pub status_bar: StatusBarConfig,
+    pub theme: ThemeConfig,
+    pub ui: UiConfig,
+    // Layout of new screens.
+    pub tiling: TilingTemplate,
+    pub templates: Vec<WorkspaceTemplate>,
+    pub startup: StartupConfig,
+    pub scripts: Vec<ScriptConfig>,
+    pub macros: HashMap<char, Vec<String>>,
+    pub commands: Vec<CompositeConfig>,
+    pub path: Option<PathBuf>,
 }
 
-fn parse_keybind(input: Vec<&str>) -> (Key, Modifiers) {
+pub fn parse_keybind(input: Vec<&str>) -> (Key, Modifiers) {
     let mut key = Key::Unidentified;
     let mut modifiers = Modifiers::empty();
 
@@ -31,6 +66,25 @@ fn parse_keybind(input: Vec<&str>) -> (Key, Modifiers) {
             "esc" => {
                 key = Key::Named(iced::keyboard::key::Named::Escape);
             }
+            "up" => {
+                key = Key::Named(iced::keyboard::key::Named::ArrowUp);
+            }
+            "down" => {
+                key = Key::Named(iced::keyboard::key::Named::ArrowDown);
+            }
+            "left" => {
+                key = Key::Named(iced::keyboard::key::Named::ArrowLeft);
+            }
+            "right" => {
+                key = Key::Named(iced::keyboard::key::Named::ArrowRight);
+            }
+            "backspace" => {
+                key = Key::Named(iced::keyboard::key::Named::Backspace);
+            }
+            // Separator character can't be used as a key name directly.
+            "minus" => {
+                key = Key::Character("-".into());
+            }
             _ => {
                 key = Key::Character(part.into());
             }
@@ -40,38 +94,688 @@ fn parse_keybind(input: Vec<&str>) -> (Key, Modifiers) {
     (key, modifiers)
 }
 
+// Keybind with an optional trigger in front, e.g. `release-alt` or `hold-space`.
+// Modifier alone stands for releasing it, with `Key::Unidentified` as the key.
+pub fn parse_trigger(input: Vec<&str>) -> (Trigger, (Key, Modifiers)) {
+    match input.split_first() {
+        Some((first, rest)) if let Some(trigger) = Trigger::from_name(first) => {
+            (trigger, parse_keybind(rest.to_vec()))
+        }
+        _ => (Trigger::Press, parse_keybind(input)),
+    }
+}
+
+pub fn format_trigger(trigger: Trigger, keybind: &(Key, Modifiers)) -> String {
+    match trigger {
+        Trigger::Press => format_keybind(keybind),
+        trigger => format!("{}-{}", trigger.name(), format_keybind(keybind)),
+    }
+}
+
+// Inverse of `parse_keybind`, e.g. for reports.
+pub fn format_keybind((key, modifiers): &(Key, Modifiers)) -> String {
+    use iced::keyboard::key::Named;
+
+    let mut parts = Vec::new();
+    if modifiers.contains(Modifiers::COMMAND) {
+        parts.push("cmd".to_string());
+    }
+    // Command is control on some platforms.
+    if modifiers.contains(Modifiers::CTRL) && Modifiers::CTRL != Modifiers::COMMAND {
+        parts.push("ctrl".to_string());
+    }
+    if modifiers.alt() {
+        parts.push("alt".to_string());
+    }
+    if modifiers.shift() {
+        parts.push("shift".to_string());
+    }
+
+    parts.extend(match key {
+        Key::Unidentified => None,
+        Key::Named(Named::Space) => Some("space".to_string()),
+        Key::Named(Named::Tab) => Some("tab".to_string()),
+        Key::Named(Named::Enter) => Some("enter".to_string()),
+        Key::Named(Named::Escape) => Some("esc".to_string()),
+        Key::Named(Named::ArrowUp) => Some("up".to_string()),
+        Key::Named(Named::ArrowDown) => Some("down".to_string()),
+        Key::Named(Named::ArrowLeft) => Some("left".to_string()),
+        Key::Named(Named::ArrowRight) => Some("right".to_string()),
+        Key::Named(Named::Backspace) => Some("backspace".to_string()),
+        Key::Character(text) if text == "-" => Some("minus".to_string()),
+        Key::Character(text) => Some(text.to_string()),
+        key => Some(format!("{key:?}")),
+    });
+
+    parts.join("-")
+}
+
+// Keys are command handles bound to a keybind, except for `extends`
+// listing keymaps to fall back to and `unbind` listing keybinds hidden from them.
+fn parse_keymap(name: &str, table: &toml::Table) -> Keymap {
+    let mut keymap = Keymap::new(name.to_string());
+
+    for (command_handle, value) in table {
+        match (command_handle.as_str(), value) {
+            ("extends", value) => keymap.extends = parse_string_list(value),
+            ("unbind", value) => {
+                keymap.unbound = parse_string_list(value)
+                    .iter()
+                    .map(|keybind| parse_trigger(keybind.split("-").collect()))
+                    .collect();
+            }
+            (_, Value::String(keybind)) => {
+                let (trigger, keybind) = parse_trigger(keybind.split("-").collect());
+                // Binding later in the file wins, the overridden one is kept for reporting.
+                let previous = if trigger == Trigger::Press {
+                    keymap
+                        .mapping
+                        .insert(keybind.clone(), KeymapNode::Command(command_handle.clone()))
+                        .map(|KeymapNode::Command(previous)| previous)
+                } else {
+                    keymap
+                        .triggered
+                        .insert((trigger, keybind.clone()), command_handle.clone())
+                };
+
+                if let Some(previous) = previous {
+                    keymap
+                        .overridden
+                        .entry((trigger, keybind))
+                        .or_default()
+                        .push(previous);
+                }
+            }
+            _ => {
+                tracing::warn!(keymap = %name, command = %command_handle, "Keybind must be a string")
+            }
+        }
+    }
+
+    keymap
+}
+
+// Either a single string or a list of them.
+fn parse_string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(value) => vec![value.clone()],
+        Value::Array(values) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Segment is either a plain name or a table with `segment` name and its options.
+fn parse_segment(value: &Value) -> Option<Segment> {
+    match value {
+        Value::String(name) => Segment::from_name(name),
+        Value::Table(table) => match table.get("segment").and_then(Value::as_str)? {
+            "clock" => Some(Segment::Clock {
+                format: table
+                    .get("format")
+                    .and_then(Value::as_str)
+                    .and_then(|format| {
+                        let parsed = time::format_description::parse_owned::<2>(format);
+                        if let Err(err) = &parsed {
+                            tracing::warn!(format, %err, "Invalid clock format, using default");
+                        }
+                        parsed.ok()
+                    })
+                    .unwrap_or_else(default_clock_format),
+            }),
+            "shell" => Some(Segment::Shell {
+                command: table.get("command").and_then(Value::as_str)?.to_string(),
+                interval: table
+                    .get("interval")
+                    .and_then(Value::as_integer)
+                    .and_then(|secs| u64::try_from(secs).ok())
+                    .map(std::time::Duration::from_secs)
+                    .unwrap_or(DEFAULT_SHELL_INTERVAL),
+            }),
+            name => Segment::from_name(name),
+        },
+        _ => None,
+    }
+}
+
+fn parse_segments(table: &toml::Table, key: &str) -> Option<Vec<Segment>> {
+    let values = table.get(key)?.as_array()?;
+
+    Some(
+        values
+            .iter()
+            .filter_map(|value| {
+                let segment = parse_segment(value);
+                if segment.is_none() {
+                    tracing::warn!(?value, "Unknown status bar segment");
+                }
+                segment
+            })
+            .collect(),
+    )
+}
+
+fn parse_color(table: &toml::Table, key: &str) -> Option<iced::Color> {
+    let value = table.get(key)?.as_str()?;
+    let color = iced::Color::parse(value);
+    if color.is_none() {
+        tracing::warn!(key, value, "Invalid theme color");
+    }
+    color
+}
+
+fn parse_custom_palette(table: &toml::Table) -> Option<CustomPalette> {
+    let defaults = iced::theme::Palette::LIGHT;
+
+    Some(CustomPalette {
+        palette: iced::theme::Palette {
+            background: parse_color(table, "background")?,
+            text: parse_color(table, "text")?,
+            primary: parse_color(table, "primary")?,
+            success: parse_color(table, "success").unwrap_or(defaults.success),
+            danger: parse_color(table, "danger").unwrap_or(defaults.danger),
+        },
+        focus_border: parse_color(table, "focus-border"),
+        collapsed_tile: parse_color(table, "collapsed-tile"),
+    })
+}
+
+fn parse_size(table: &toml::Table, key: &str) -> Option<f32> {
+    match table.get(key)? {
+        Value::Integer(value) => Some(*value as f32),
+        Value::Float(value) => Some(*value as f32),
+        value => {
+            tracing::warn!(key, ?value, "Invalid UI size");
+            None
+        }
+    }
+    .filter(|size| *size >= 0.0)
+}
+
+fn parse_ui(table: &toml::Table) -> UiConfig {
+    let defaults = UiConfig::default();
+
+    UiConfig {
+        font: table
+            .get("font")
+            .and_then(Value::as_str)
+            .map(font_with_name)
+            .unwrap_or(defaults.font),
+        monospace_font: table
+            .get("monospace-font")
+            .and_then(Value::as_str)
+            .map(font_with_name)
+            .unwrap_or(defaults.monospace_font),
+        font_size: parse_size(table, "font-size").unwrap_or(defaults.font_size),
+        tile_padding: parse_size(table, "tile-padding").unwrap_or(defaults.tile_padding),
+        tile_gap: parse_size(table, "tile-gap").unwrap_or(defaults.tile_gap),
+        border_width: parse_size(table, "border-width").unwrap_or(defaults.border_width),
+        collapsed_row_height: parse_size(table, "collapsed-row-height")
+            .unwrap_or(defaults.collapsed_row_height),
+    }
+}
+
+// Leading `~` stands for home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn parse_count(table: &toml::Table, key: &str) -> Option<usize> {
+    let value = table.get(key)?;
+    let count = value
+        .as_integer()
+        .and_then(|count| usize::try_from(count).ok());
+    if count.is_none() {
+        tracing::warn!(key, ?value, "Invalid count");
+    }
+    count
+}
+
+// Activity is either a plain name or a table with `name` and `command`.
+fn parse_activity(value: &Value) -> Option<ActivityTemplate> {
+    match value {
+        Value::String(name) => Some(ActivityTemplate {
+            name: name.clone(),
+            command: None,
+        }),
+        Value::Table(table) => Some(ActivityTemplate {
+            name: table.get("name").and_then(Value::as_str)?.to_string(),
+            command: table
+                .get("command")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        }),
+        _ => None,
+    }
+}
+
+// Weights outside of the range a tile can be resized to are clamped.
+fn parse_weights(table: &toml::Table, key: &str) -> Option<Vec<u16>> {
+    let value = table.get(key)?;
+    let weights = value.as_array().and_then(|weights| {
+        weights
+            .iter()
+            .map(|weight| {
+                let weight = weight.as_integer()?;
+                Some(weight.clamp(1, i64::from(MAX_WEIGHT)) as u16)
+            })
+            .collect()
+    });
+    if weights.is_none() {
+        tracing::warn!(key, ?value, "Invalid weights");
+    }
+    weights
+}
+
+fn parse_tiling(table: &toml::Table) -> TilingTemplate {
+    TilingTemplate {
+        max_columns: parse_count(table, "max-columns"),
+        max_expanded_rows: parse_count(table, "max-expanded-rows"),
+        column_weights: parse_weights(table, "column-weights"),
+        row_weights: parse_weights(table, "row-weights"),
+    }
+}
+
+fn parse_template(name: &str, table: &toml::Table) -> WorkspaceTemplate {
+    WorkspaceTemplate {
+        workspace_name: table
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        root: table.get("root").and_then(Value::as_str).map(expand_home),
+        tiling: parse_tiling(table),
+        activities: table
+            .get("activities")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|value| {
+                let activity = parse_activity(value);
+                if activity.is_none() {
+                    tracing::warn!(template = name, ?value, "Invalid activity");
+                }
+                activity
+            })
+            .collect(),
+        ..WorkspaceTemplate::new(name)
+    }
+}
+
+// Script source is either inline `source` or a `file` to read it from.
+fn parse_script(handle: &str, table: &toml::Table) -> Option<ScriptConfig> {
+    let source = match (table.get("source"), table.get("file")) {
+        (Some(Value::String(source)), None) => ScriptSource::Inline(source.clone()),
+        (None, Some(Value::String(file))) => ScriptSource::File(expand_home(file)),
+        _ => return None,
+    };
+
+    Some(ScriptConfig {
+        handle: handle.to_string(),
+        name: table
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or(handle)
+            .to_string(),
+        description: table
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        source,
+    })
+}
+
+// Register is a single letter, macro a list of command handles.
+fn parse_macro(register: &str, value: &Value) -> Option<(char, Vec<String>)> {
+    let mut chars = register.chars();
+    let register = chars
+        .next()
+        .filter(|char| is_register(*char) && chars.next().is_none())?;
+
+    let handles = value
+        .as_array()?
+        .iter()
+        .map(|handle| handle.as_str().map(str::to_string))
+        .collect::<Option<_>>()?;
+
+    Some((register, handles))
+}
+
+// Composite is either a `"a; b"` string of handles or a table with `run`, `name` and `description`.
+fn parse_composite(handle: &str, value: &Value) -> Option<CompositeConfig> {
+    let (run, table) = match value {
+        Value::String(run) => (run.as_str(), None),
+        Value::Table(table) => (table.get("run")?.as_str()?, Some(table)),
+        _ => return None,
+    };
+    let field = |key| {
+        table
+            .and_then(|table| table.get(key))
+            .and_then(Value::as_str)
+    };
+
+    Some(CompositeConfig {
+        handle: handle.to_string(),
+        name: field("name").unwrap_or(handle).to_string(),
+        description: field("description").unwrap_or(run).to_string(),
+        steps: run
+            .split(';')
+            .map(str::trim)
+            .filter(|step| !step.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+// Rewrites configuration file in place, keeping its formatting and comments.
+// Nothing is written when the edit fails.
+pub fn edit_config(
+    path: &Path,
+    edit: impl FnOnce(&mut toml_edit::DocumentMut) -> Result<()>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut document = content
+        .parse::<toml_edit::DocumentMut>()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    edit(&mut document)?;
+
+    std::fs::write(path, document.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+// Table under `key`, created when missing.
+pub fn edit_table<'a>(
+    parent: &'a mut dyn toml_edit::TableLike,
+    key: &str,
+) -> Result<&'a mut dyn toml_edit::TableLike> {
+    parent
+        .entry(key)
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            ConfigError::NotATable {
+                key: key.to_string(),
+            }
+            .into()
+        })
+}
+
 impl Config {
     pub fn from_toml(path: impl Into<PathBuf>) -> Self {
-        use toml::Table;
+        Self::try_from_toml(path).expect("Failed to load configuration file")
+    }
+
+    // Fallible loading, for reporting broken configuration instead of panicking.
+    pub fn try_from_toml(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let _span = tracing::info_span!("config", path = %path.display()).entered();
+
         // read file
-        let content =
-            std::fs::read_to_string(path.into()).expect("Failed to read keymap configuration file");
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config = Self::try_parse(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        Ok(Self {
+            path: Some(path),
+            ..config
+        })
+    }
+
+    // Parses in-memory configuration in tests.
+    #[cfg(test)]
+    pub fn parse(content: &str) -> Self {
+        Self::try_parse(content).unwrap()
+    }
+
+    pub fn try_parse(content: &str) -> Result<Self, toml::de::Error> {
+        use toml::Table;
 
         // parse toml
-        let table = content.parse::<Table>().unwrap();
+        let table = content.parse::<Table>()?;
 
         let mut keymaps = HashMap::new();
 
         if let Some(Value::Table(keymaps_table)) = table.get("keymaps") {
             for (keymap_name, keymap_table) in keymaps_table {
                 if let Some(keymap) = keymap_table.as_table() {
-                    let mut keymap_instance = Keymap::new(keymap_name.clone());
+                    keymaps.insert(keymap_name.clone(), parse_keymap(keymap_name, keymap));
+                }
+            }
+        }
 
-                    for (command_handle, keybind) in keymap {
-                        if let Some(msg_str) = keybind.as_str() {
-                            let keys = msg_str.split("-").collect();
+        let mut mode_keymaps = HashMap::new();
 
-                            keymap_instance.mapping.insert(
-                                parse_keybind(keys),
-                                KeymapNode::Command(command_handle.clone()),
-                            );
-                        }
+        if let Some(Value::Table(mode_keymaps_table)) = table.get("mode-keymaps") {
+            for (mode, keymap) in mode_keymaps_table {
+                match keymap.as_str() {
+                    Some(keymap) => {
+                        mode_keymaps.insert(mode.clone(), keymap.to_string());
                     }
-                    keymaps.insert(keymap_name.clone(), keymap_instance);
+                    None => tracing::warn!(mode = %mode, "Mode keymap must be a keymap name"),
                 }
             }
         }
 
-        Self { keymaps }
+        let mut status_bar = StatusBarConfig::default();
+
+        if let Some(Value::Table(status_bar_table)) = table.get("status-bar") {
+            if let Some(segments) = parse_segments(status_bar_table, "left") {
+                status_bar.left = segments;
+            }
+            if let Some(segments) = parse_segments(status_bar_table, "center") {
+                status_bar.center = segments;
+            }
+            if let Some(segments) = parse_segments(status_bar_table, "right") {
+                status_bar.right = segments;
+            }
+        }
+
+        let mut theme = ThemeConfig::default();
+
+        if let Some(Value::Table(theme_table)) = table.get("theme") {
+            theme.name = theme_table
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+
+            if let Some(Value::Table(custom_table)) = theme_table.get("custom") {
+                theme.custom = parse_custom_palette(custom_table);
+                if theme.custom.is_none() {
+                    tracing::warn!("Custom theme requires background, text and primary colors");
+                }
+            }
+        }
+
+        let ui = match table.get("ui") {
+            Some(Value::Table(ui_table)) => parse_ui(ui_table),
+            _ => UiConfig::default(),
+        };
+
+        let tiling = match table.get("tiling") {
+            Some(Value::Table(tiling_table)) => parse_tiling(tiling_table),
+            _ => TilingTemplate::default(),
+        };
+
+        let mut templates = Vec::new();
+
+        if let Some(Value::Table(templates_table)) = table.get("templates") {
+            for (name, template) in templates_table {
+                match template.as_table() {
+                    Some(template) => templates.push(parse_template(name, template)),
+                    None => tracing::warn!(template = %name, "Template must be a table"),
+                }
+            }
+        }
+
+        let mut startup = StartupConfig::default();
+
+        if let Some(Value::Table(startup_table)) = table.get("startup") {
+            startup.workspaces = startup_table
+                .get("workspaces")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect();
+        }
+
+        let mut scripts = Vec::new();
+
+        if let Some(Value::Table(scripts_table)) = table.get("scripts") {
+            for (handle, script) in scripts_table {
+                match script
+                    .as_table()
+                    .and_then(|script| parse_script(handle, script))
+                {
+                    Some(script) => scripts.push(script),
+                    None => tracing::warn!(
+                        script = %handle,
+                        "Script must be a table with either source or file"
+                    ),
+                }
+            }
+        }
+
+        let mut macros = HashMap::new();
+
+        if let Some(Value::Table(macros_table)) = table.get("macros") {
+            for (register, value) in macros_table {
+                match parse_macro(register, value) {
+                    Some((register, handles)) => {
+                        macros.insert(register, handles);
+                    }
+                    None => tracing::warn!(
+                        register = %register,
+                        "Macro must be a list of handles under a single letter register"
+                    ),
+                }
+            }
+        }
+
+        let mut commands = Vec::new();
+
+        if let Some(Value::Table(commands_table)) = table.get("commands") {
+            for (handle, value) in commands_table {
+                match parse_composite(handle, value) {
+                    Some(composite) => commands.push(composite),
+                    None => tracing::warn!(
+                        command = %handle,
+                        "Command must be a string of handles or a table with run"
+                    ),
+                }
+            }
+        }
+
+        tracing::info!(
+            keymaps = keymaps.len(),
+            templates = templates.len(),
+            scripts = scripts.len(),
+            "Loaded configuration"
+        );
+
+        Ok(Self {
+            keymaps,
+            mode_keymaps,
+            status_bar,
+            theme,
+            ui,
+            tiling,
+            templates,
+            startup,
+            scripts,
+            macros,
+            commands,
+            path: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_bar(content: &str) -> StatusBarConfig {
+        Config::parse(&format!("[status-bar]\n{content}")).status_bar
+    }
+
+    #[test]
+    fn segments_are_parsed_by_name_or_table() {
+        let config = status_bar(
+            r#"
+            left = ["mode-stack", { segment = "workspace" }]
+            center = [{ segment = "shell", command = "date", interval = 3 }]
+            right = [{ segment = "clock", format = "[hour]" }]
+            "#,
+        );
+
+        assert_eq!(config.left, [Segment::ModeStack, Segment::Workspace]);
+        assert_eq!(
+            config.center,
+            [Segment::Shell {
+                command: "date".to_string(),
+                interval: std::time::Duration::from_secs(3),
+            }]
+        );
+        assert_eq!(
+            config.right,
+            [Segment::Clock {
+                format: time::format_description::parse_owned::<2>("[hour]").unwrap(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_segments_are_skipped() {
+        let config = status_bar(
+            r#"
+            left = ["nope", { segment = "shell" }, 3, "macro"]
+            "#,
+        );
+
+        assert_eq!(config.left, [Segment::Macro]);
+        assert_eq!(config.right, StatusBarConfig::default().right);
+    }
+
+    #[test]
+    fn shell_interval_defaults_when_invalid() {
+        let config = status_bar(
+            r#"
+            left = [{ segment = "shell", command = "date", interval = -1 }]
+            "#,
+        );
+
+        assert_eq!(
+            config.left,
+            [Segment::Shell {
+                command: "date".to_string(),
+                interval: DEFAULT_SHELL_INTERVAL,
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_clock_format_falls_back_to_default() {
+        let config = status_bar(
+            r#"
+            left = [{ segment = "clock", format = "[hour" }]
+            "#,
+        );
+
+        assert_eq!(
+            config.left,
+            [Segment::Clock {
+                format: default_clock_format(),
+            }]
+        );
     }
 }
@@ -0,0 +1,63 @@
+use iced::Element;
+
+use crate::config::format_keybind;
+use crate::elements::picker::selected_item;
+use crate::message::Message;
+use crate::state::binding_editor::BindingEditor;
+
+pub fn binding_editor(editor: &BindingEditor, capturing: bool) -> Element<'_, Message> {
+    use iced::Length;
+    use iced::widget::{Column, column, container, row, scrollable, text};
+
+    let rows = editor.rows.iter().enumerate().fold(
+        Column::new().spacing(2),
+        |column, (index, binding)| {
+            let item = container(row!(
+                text(&binding.name).width(Length::FillPortion(2)),
+                text(&binding.handle).width(Length::FillPortion(2)),
+                text(binding.bindings.join(", ")).width(Length::FillPortion(3)),
+            ))
+            .padding([2, 10])
+            .width(Length::Fill);
+
+            column.push(if index == editor.selected {
+                item.style(selected_item)
+            } else {
+                item
+            })
+        },
+    );
+
+    let handle = editor
+        .selected_row()
+        .map(|row| row.handle.as_str())
+        .unwrap_or_default();
+    let status = match &editor.pending {
+        _ if capturing => format!("Press keys for {handle}, esc cancels"),
+        Some(pending) => format!(
+            "{} {}, confirm to bind it anyway",
+            format_keybind(&pending.keybind),
+            pending
+                .conflicts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => String::new(),
+    };
+
+    container(
+        column!(
+            text(format!("Keybindings in {}", editor.keymap)),
+            scrollable(rows).height(Length::Fill).width(Length::Fill),
+            text(status),
+        )
+        .spacing(10),
+    )
+    .padding(10)
+    .width(900)
+    .height(500)
+    .style(container::bordered_box)
+    .into()
+}
@@ -0,0 +1,32 @@
+use iced::{Element, Font};
+
+use crate::logging::SessionLog;
+use crate::message::Message;
+
+pub fn log_viewer(session_log: Option<&SessionLog>, font: Font) -> Element<'static, Message> {
+    use iced::Length;
+    use iced::widget::{Column, column, container, scrollable, text};
+
+    let lines = session_log.map(SessionLog::lines).unwrap_or_default();
+
+    let content: Element<'static, Message> = if lines.is_empty() {
+        text("Session log is empty").into()
+    } else {
+        let lines = lines.into_iter().fold(Column::new(), |column, line| {
+            column.push(text(line).font(font).size(12))
+        });
+
+        scrollable(lines)
+            .anchor_bottom()
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .into()
+    };
+
+    container(column!(text("Session Log"), content).spacing(5))
+        .padding(10)
+        .width(900)
+        .height(500)
+        .style(container::bordered_box)
+        .into()
+}
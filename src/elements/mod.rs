@@ -1,4 +1,10 @@
+mod binding_editor;
+mod log;
+mod notification;
+mod overview;
+mod picker;
 mod screen;
+mod status_bar;
 mod tiled;
 mod workspace;
 
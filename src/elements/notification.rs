@@ -0,0 +1,109 @@
+use iced::Element;
+use time::OffsetDateTime;
+
+use crate::message::Message;
+use crate::state::notification::{Notification, Notifications, Severity};
+use crate::state::status_bar::local_offset;
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "INFO",
+        Severity::Error => "ERROR",
+    }
+}
+
+fn severity_box(severity: Severity) -> impl Fn(&iced::Theme) -> iced::widget::container::Style {
+    use iced::widget::container::{Style, bordered_box};
+
+    move |theme: &iced::Theme| {
+        let palette = theme.extended_palette();
+        let color = match severity {
+            Severity::Info => palette.primary.base.color,
+            Severity::Error => palette.danger.base.color,
+        };
+
+        Style {
+            border: iced::Border {
+                color,
+                ..bordered_box(theme).border
+            },
+            ..bordered_box(theme)
+        }
+    }
+}
+
+fn notification_line(notification: &Notification) -> Element<'_, Message> {
+    use iced::widget::{row, text};
+
+    let timestamp = notification.timestamp.to_offset(local_offset());
+
+    row!(
+        text(format!(
+            "{:02}:{:02}:{:02}",
+            timestamp.hour(),
+            timestamp.minute(),
+            timestamp.second()
+        )),
+        text(severity_label(notification.severity)).width(50),
+        text(&notification.message),
+    )
+    .spacing(10)
+    .into()
+}
+
+pub fn toasts(notifications: &Notifications) -> Option<Element<'_, Message>> {
+    use iced::widget::{Column, container, text};
+    use iced::{Alignment, Length};
+
+    let mut toasts = notifications.toasts(OffsetDateTime::now_utc()).peekable();
+    toasts.peek()?;
+
+    let column = toasts.fold(Column::new().spacing(5), |column, notification| {
+        column.push(
+            container(text(&notification.message))
+                .padding(10)
+                .max_width(400)
+                .style(severity_box(notification.severity)),
+        )
+    });
+
+    Some(
+        container(column)
+            .padding(10)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::End)
+            .align_y(Alignment::End)
+            .into(),
+    )
+}
+
+pub fn notification_history(notifications: &Notifications) -> Element<'_, Message> {
+    use iced::Length;
+    use iced::widget::{Column, column, container, scrollable, text};
+
+    if notifications.history().next().is_none() {
+        return container(text("No notifications"))
+            .padding(10)
+            .style(container::bordered_box)
+            .into();
+    }
+
+    // Most recent notifications go first.
+    let lines = notifications
+        .history()
+        .rev()
+        .fold(Column::new().spacing(5), |column, notification| {
+            column.push(notification_line(notification))
+        });
+
+    container(column!(
+        text("Notifications"),
+        scrollable(lines).height(Length::Fill).width(Length::Fill),
+    ))
+    .padding(10)
+    .width(600)
+    .height(400)
+    .style(container::bordered_box)
+    .into()
+}
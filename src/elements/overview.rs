@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use iced::Element;
+
+use crate::elements::tiled::{TileAppearance, focused_box, tile_box};
+use crate::message::Message;
+use crate::state::{
+    Uid,
+    activity::Activity,
+    overview::{Overview, overview_columns},
+    workspace::Workspace,
+};
+
+// Summary card with workspace name, root and names of its activities.
+fn card<'a>(
+    workspace: &'a Workspace,
+    activities: &'a HashMap<Uid, Activity>,
+    selected: bool,
+    appearance: TileAppearance,
+) -> Element<'a, Message> {
+    use iced::Length;
+    use iced::widget::{Column, column, container, text};
+
+    let mut header = column!(text(&workspace.name));
+    if let Some(root) = &workspace.root {
+        header = header.push(text(root.display().to_string()).size(12));
+    }
+
+    let activities = workspace
+        .activity_ids
+        .iter()
+        .filter_map(|id| activities.get(id))
+        .fold(Column::new().spacing(2), |column, activity| {
+            column.push(text(format!("- {}", activity.name)).size(12))
+        });
+
+    let card = container(column!(header, activities).spacing(10))
+        .padding(10)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    if selected {
+        card.style(focused_box(appearance)).into()
+    } else {
+        card.style(tile_box(appearance)).into()
+    }
+}
+
+// Workspaces matching the filter as a grid of cards, with the filter above them.
+pub fn overview<'a>(
+    overview: &'a Overview,
+    workspaces: Vec<&'a Workspace>,
+    activities: &'a HashMap<Uid, Activity>,
+    appearance: TileAppearance,
+) -> Element<'a, Message> {
+    use iced::Length;
+    use iced::widget::{Column, Row, column, container, horizontal_space, text};
+
+    let filter = match (overview.filtering, overview.filter.is_empty()) {
+        (true, _) => format!("Filter: {}_", overview.filter),
+        (false, false) => format!("Filter: {}", overview.filter),
+        (false, true) => String::new(),
+    };
+
+    let columns = overview_columns(workspaces.len());
+    let grid: Element<'a, Message> = if workspaces.is_empty() {
+        text("No workspaces match").into()
+    } else {
+        workspaces
+            .chunks(columns)
+            .enumerate()
+            .fold(Column::new().spacing(10), |column, (row, chunk)| {
+                let cards = chunk.iter().enumerate().fold(
+                    Row::new().spacing(10),
+                    |cards, (index, workspace)| {
+                        let selected = row * columns + index == overview.selected;
+                        cards.push(card(workspace, activities, selected, appearance))
+                    },
+                );
+                // Short last row keeps cards the same width as rows above.
+                let cards = (chunk.len()..columns).fold(cards, |cards, _| {
+                    cards.push(horizontal_space().width(Length::Fill))
+                });
+                column.push(cards.height(Length::Fill))
+            })
+            .into()
+    };
+
+    container(column!(text(filter), grid).spacing(10))
+        .padding(10)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
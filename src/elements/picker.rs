@@ -0,0 +1,41 @@
+use iced::Element;
+
+use crate::message::Message;
+use crate::state::picker::Picker;
+
+pub fn selected_item(theme: &iced::Theme) -> iced::widget::container::Style {
+    let palette = theme.extended_palette();
+
+    iced::widget::container::Style {
+        background: Some(palette.primary.base.color.into()),
+        text_color: Some(palette.primary.base.text),
+        ..iced::widget::container::Style::default()
+    }
+}
+
+pub fn picker(picker: &Picker) -> Element<'_, Message> {
+    use iced::Length;
+    use iced::widget::{Column, column, container, scrollable, text};
+
+    let items =
+        picker
+            .items
+            .iter()
+            .enumerate()
+            .fold(Column::new().spacing(2), |column, (index, item)| {
+                let item = container(text(item)).padding([2, 10]).width(Length::Fill);
+
+                column.push(if index == picker.selected {
+                    item.style(selected_item)
+                } else {
+                    item
+                })
+            });
+
+    container(column!(text(&picker.title), scrollable(items).width(Length::Fill)).spacing(10))
+        .padding(10)
+        .width(400)
+        .max_height(400)
+        .style(container::bordered_box)
+        .into()
+}
@@ -1,65 +1,93 @@
-use iced::Element;
+use iced::{Element, window};
 
 use crate::message::Message;
 use crate::state::State;
-use crate::state::mode::ViewMode;
+use crate::state::binding_editor::BindingEditorActions;
+use crate::state::mode::{Mode, ModeActions};
+use crate::state::overview::{overview_workspace_ids, shown_overview};
+use crate::state::picker::PickerActions;
+use crate::state::screen::Screen;
 
-pub fn screen(state: &State) -> Element<Message> {
-    use crate::elements::tiled::tiled;
-    use crate::state::mode::Mode;
-    use iced::Length;
-    use iced::widget::{column, container, row, text};
+pub fn screen<'a>(
+    state: &'a State,
+    window: window::Id,
+    screen: &'a Screen,
+) -> Element<'a, Message> {
+    use crate::elements::binding_editor::binding_editor;
+    use crate::elements::log::log_viewer;
+    use crate::elements::notification::{notification_history, toasts};
+    use crate::elements::overview::overview;
+    use crate::elements::picker::picker;
+    use crate::elements::status_bar::status_bar;
+    use crate::elements::tiled::{TileAppearance, tiled};
+    use crate::elements::workspace::workspace as workspace_element;
+    use crate::state::screen::TransientTool;
+    use iced::widget::{column, container, stack, text};
 
-    let screen = &state.screen;
-    column!(
+    // Picker and binding editor wait for input, so they are shown over other tools
+    // of the focused screen, which receives the keys.
+    let focused = state.screen().window == Some(window);
+    let modal_item = match screen.transient_tool {
+        _ if focused && let Some(active) = state.active_picker() => Some(picker(active)),
+        _ if focused && let Some(active) = state.active_binding_editor() => {
+            Some(binding_editor(active, state.mode == Mode::KeyCapture))
+        }
+        Some(TransientTool::Notifications) => Some(notification_history(&state.notifications)),
+        Some(TransientTool::Log) => Some(log_viewer(
+            crate::logging::session_log(),
+            state.ui.config.monospace_font,
+        )),
+        None => screen.transient_tool_id.map(|id| {
+            container(text(format!("transient tool {}", id)))
+                .padding(10)
+                .style(container::bordered_box)
+                .into()
+        }),
+    };
+
+    let appearance = TileAppearance {
+        colors: state.themes.tile_colors(),
+        ui: state.ui.config,
+    };
+
+    // Overview takes the place of tiled workspaces until a workspace is picked.
+    let workspaces = if let Some(active) = shown_overview(state, screen) {
+        overview(
+            active,
+            overview_workspace_ids(state, active)
+                .iter()
+                .filter_map(|id| state.workspaces.get(id))
+                .collect(),
+            &state.activities,
+            appearance,
+        )
+    } else {
         tiled(
+            window,
             &screen.tiling,
             screen
                 .workspace_ids
                 .iter()
-                .map(|id| {
+                .filter_map(|id| state.workspaces.get(id))
+                .map(|workspace| {
                     (
-                        *id,
-                        format!("- {}", id),
-                        container(text(format!("worspace {}", id)))
-                            .padding(10)
-                            .height(Length::Fill)
-                            .width(Length::Fill)
-                            .style(container::bordered_box)
-                            .into(),
+                        workspace.id,
+                        format!("- {}", workspace.name),
+                        workspace_element(workspace, &state.activities),
                     )
                 })
                 .collect(),
-            screen.transient_tool_id.map(|id| {
-                container(text(format!("transient tool {}", id)))
-                    .padding(10)
-                    .style(container::bordered_box)
-                    .into()
-            }),
-            match state.mode {
-                Mode::Workspace { id, .. } => id,
-                Mode::View {
-                    mode: ViewMode::Workspace { id },
-                } => id,
-                _ => None,
-            },
-        ),
-        row!(text(format!(
-            " MODE: {:?} CMD: {}",
-            match state.mode {
-                Mode::None => "-".to_string(),
-                Mode::Workspace { id } => format!("WSP {:?}", id),
-                Mode::View {
-                    mode: ViewMode::Workspace { .. },
-                } => "WSP VIEW".to_string(),
-                _ => "UNKNOWN".to_string(),
-            },
-            if let Some(command) = &state.last_command {
-                command.handle()
-            } else {
-                "-"
-            }
-        ))),
-    )
-    .into()
+            modal_item,
+            state.current_workspace_id(),
+            appearance,
+        )
+    };
+
+    let content = column!(workspaces, status_bar(state));
+
+    if let Some(toasts) = toasts(&state.notifications) {
+        stack![content, toasts].into()
+    } else {
+        content.into()
+    }
 }
@@ -0,0 +1,135 @@
+use iced::Element;
+use iced::widget::container;
+use time::format_description::OwnedFormatItem;
+
+use crate::message::Message;
+use crate::state::State;
+use crate::state::mode::ModeActions;
+use crate::state::status_bar::{Segment, local_now};
+
+fn segment_box(theme: &iced::Theme) -> container::Style {
+    let palette = theme.extended_palette();
+
+    container::Style {
+        background: Some(palette.background.weak.color.into()),
+        text_color: Some(palette.background.weak.text),
+        ..container::Style::default()
+    }
+}
+
+fn accent_box(theme: &iced::Theme) -> container::Style {
+    let palette = theme.extended_palette();
+
+    container::Style {
+        background: Some(palette.primary.base.color.into()),
+        text_color: Some(palette.primary.base.text),
+        ..container::Style::default()
+    }
+}
+
+fn danger_box(theme: &iced::Theme) -> container::Style {
+    let palette = theme.extended_palette();
+
+    container::Style {
+        background: Some(palette.danger.base.color.into()),
+        text_color: Some(palette.danger.base.text),
+        ..container::Style::default()
+    }
+}
+
+fn clock(format: &OwnedFormatItem) -> String {
+    local_now()
+        .format(format)
+        .unwrap_or_else(|_| "invalid clock format".to_string())
+}
+
+// Modes from root to current one, with current mode highlighted.
+fn mode_breadcrumb(state: &State) -> Element<'_, Message> {
+    use iced::widget::{Row, text};
+
+    let crumbs = state
+        .mode_history
+        .iter()
+        .map(|mode| (mode, segment_box as fn(&iced::Theme) -> container::Style))
+        .chain(std::iter::once((&state.mode, accent_box as _)));
+
+    crumbs
+        .enumerate()
+        .fold(Row::new().spacing(2), |row, (index, (mode, style))| {
+            let row = if index > 0 { row.push(text(">")) } else { row };
+            row.push(container(text(mode.label())).padding([0, 5]).style(style))
+        })
+        .into()
+}
+
+fn segment<'a>(state: &'a State, segment: &'a Segment) -> Option<Element<'a, Message>> {
+    use iced::widget::text;
+
+    let (content, style): (String, fn(&iced::Theme) -> container::Style) = match segment {
+        Segment::ModeStack => return Some(mode_breadcrumb(state)),
+        Segment::Workspace => (
+            state
+                .current_workspace_id()
+                .and_then(|id| state.workspaces.get(&id))
+                .map(|workspace| workspace.name.clone())?,
+            segment_box,
+        ),
+        Segment::PendingKeys => (state.pending_count?.to_string(), segment_box),
+        Segment::Macro => (
+            state
+                .macros
+                .recording
+                .as_ref()
+                .map(|recording| format!("REC {}", recording.register))?,
+            accent_box,
+        ),
+        Segment::LastCommand => (
+            state
+                .last_command
+                .as_ref()
+                .map(|last| last.command.handle().to_string())?,
+            segment_box,
+        ),
+        Segment::ErrorCount => match state.notifications.error_count() {
+            0 => return None,
+            count => (format!("ERR {}", count), danger_box),
+        },
+        Segment::Clock { format } => (clock(format), segment_box),
+        Segment::Shell { command, .. } => (
+            state.status_bar.shell_outputs.get(command)?.clone(),
+            segment_box,
+        ),
+    };
+
+    Some(container(text(content)).padding([0, 5]).style(style).into())
+}
+
+fn segments<'a>(
+    state: &'a State,
+    segments: &'a [Segment],
+    align: iced::alignment::Horizontal,
+) -> Element<'a, Message> {
+    use iced::Length;
+    use iced::widget::Row;
+
+    let row = segments
+        .iter()
+        .filter_map(|s| segment(state, s))
+        .fold(Row::new().spacing(5), Row::push);
+
+    container(row).width(Length::Fill).align_x(align).into()
+}
+
+pub fn status_bar(state: &State) -> Element<'_, Message> {
+    use iced::alignment::Horizontal;
+    use iced::widget::row;
+
+    let config = &state.status_bar.config;
+
+    row!(
+        segments(state, &config.left, Horizontal::Left),
+        segments(state, &config.center, Horizontal::Center),
+        segments(state, &config.right, Horizontal::Right),
+    )
+    .into()
+}
@@ -1,205 +1,283 @@
 use iced::widget::text;
+use iced::window;
 
 use crate::{
     message::Message,
-    state::{Uid, tiling::Tiling},
+    state::{
+        Uid,
+        theme::TileColors,
+        tiling::{Divider, Placement, Tiling},
+        ui::UiConfig,
+    },
 };
 
 pub type TiledItem<'a> = (Uid, String, iced::Element<'a, Message>);
 
-enum FoldingDirection {
-    Up,
-    Down,
-}
+// Smallest thickness of dividers between expanded tiles, so they can be grabbed.
+const DIVIDER_SIZE: f32 = 4.0;
 
-fn collapsed_rows<'a>(
-    rows_count: usize,
-    columns_count: usize,
-    items_iter: &mut std::vec::IntoIter<TiledItem<'a>>,
-    folding_direction: FoldingDirection,
-) -> iced::Element<'a, Message> {
-    use iced::Length;
-    use iced::widget::{container, text};
+// Theme colors and UI sizes used to draw tiles.
+#[derive(Debug, Clone, Copy)]
+pub struct TileAppearance {
+    pub colors: TileColors,
+    pub ui: UiConfig,
+}
 
-    let mut column_element = iced::widget::Column::new();
+pub fn tile_box(
+    appearance: TileAppearance,
+) -> impl Fn(&iced::Theme) -> iced::widget::container::Style {
+    use iced::widget::container::Style;
 
-    for row_index in 0..rows_count {
-        let mut row_element = iced::widget::Row::new();
+    move |theme| {
+        let palette = theme.extended_palette();
+
+        Style {
+            background: Some(palette.background.weak.color.into()),
+            text_color: Some(palette.background.weak.text),
+            border: iced::Border {
+                width: appearance.ui.border_width,
+                radius: 0.0.into(),
+                color: palette.background.strong.color,
+            },
+            ..Style::default()
+        }
+    }
+}
 
-        for _ in 0..columns_count {
-            if let Some((_id, title, _item)) = items_iter.next() {
-                let is_folded_item = match folding_direction {
-                    FoldingDirection::Up => row_index != rows_count - 1,
-                    FoldingDirection::Down => row_index != 0,
-                };
+pub fn collapsed_box(
+    appearance: TileAppearance,
+) -> impl Fn(&iced::Theme) -> iced::widget::container::Style {
+    use iced::widget::container::Style;
 
-                row_element = if is_folded_item {
-                    row_element.push(
-                        iced::widget::container(iced::widget::Column::new())
-                            .padding(5)
-                            .height(Length::Shrink)
-                            .width(Length::Fill)
-                            .clip(true)
-                            .style(container::bordered_box),
-                    )
-                } else {
-                    row_element.push(
-                        iced::widget::container(text(title))
-                            .padding(10)
-                            .max_height(50)
-                            .width(Length::Fill)
-                            .clip(true)
-                            .style(container::bordered_box),
-                    )
-                };
-            }
+    move |theme| {
+        let palette = theme.extended_palette();
+
+        Style {
+            background: Some(
+                appearance
+                    .colors
+                    .collapsed_tile
+                    .unwrap_or(palette.background.strong.color)
+                    .into(),
+            ),
+            text_color: Some(palette.background.strong.text),
+            ..tile_box(appearance)(theme)
         }
-
-        column_element = column_element.push(row_element);
     }
-
-    column_element.into()
 }
 
-pub fn focused_box(theme: &iced::Theme) -> iced::widget::container::Style {
+pub fn focused_box(
+    appearance: TileAppearance,
+) -> impl Fn(&iced::Theme) -> iced::widget::container::Style {
     use iced::widget::container::Style;
 
-    let palette = theme.extended_palette();
-
-    Style {
-        background: Some(palette.background.weak.color.into()),
-        border: iced::Border {
-            width: 1.0,
-            radius: 0.5.into(),
-            color: iced::Color::from_rgb(0.0, 0.5, 1.0),
-        },
-        ..Style::default()
+    move |theme| {
+        let palette = theme.extended_palette();
+
+        Style {
+            border: iced::Border {
+                width: appearance.ui.border_width,
+                radius: 0.5.into(),
+                color: appearance
+                    .colors
+                    .focus_border
+                    .unwrap_or(palette.primary.strong.color),
+            },
+            ..tile_box(appearance)(theme)
+        }
     }
 }
 
-pub fn expanded_rows<'a>(
-    rows_count: usize,
-    columns_count: usize,
-    items_iter: &mut std::vec::IntoIter<TiledItem<'a>>,
-    focused_id: Option<Uid>,
-) -> iced::Element<'a, Message> {
+// Draggable boundary between expanded columns or rows.
+fn divider<'a>(window: window::Id, divider: Divider, size: f32) -> iced::Element<'a, Message> {
     use iced::Length;
-    use iced::widget::container;
-
-    let mut column_element = iced::widget::Column::new();
+    use iced::mouse::Interaction;
+
+    let (width, height, interaction) = match divider {
+        Divider::Column(_) => (
+            Length::Fixed(size),
+            Length::Fill,
+            Interaction::ResizingHorizontally,
+        ),
+        Divider::Row(_) => (
+            Length::Fill,
+            Length::Fixed(size),
+            Interaction::ResizingVertically,
+        ),
+    };
+
+    iced::widget::mouse_area(iced::widget::Space::new(width, height))
+        .on_press(Message::DividerPressed(window, divider))
+        .interaction(interaction)
+        .into()
+}
 
-    for _ in 0..rows_count {
-        let mut row_element = iced::widget::Row::new();
+// Widget for a single tile, depending on where layout placed it.
+fn tile<'a>(
+    placement: Placement,
+    (id, title, item): TiledItem<'a>,
+    width: iced::Length,
+    is_focused: bool,
+    appearance: TileAppearance,
+) -> iced::Element<'a, Message> {
+    use iced::Length;
 
-        for _ in 0..columns_count {
-            if let Some((id, _title, item)) = items_iter.next() {
-                let mut item = iced::widget::container(item)
-                    .padding(10)
-                    .height(Length::Fill)
-                    .width(Length::Fill)
-                    .style(container::bordered_box);
+    let ui = appearance.ui;
 
-                if focused_id == Some(id) {
-                    item = item.style(focused_box);
-                }
+    let element: iced::Element<'a, Message> = match placement {
+        Placement::Expanded => {
+            let mut item = iced::widget::container(item)
+                .padding(ui.tile_padding)
+                .height(Length::Fill)
+                .width(width)
+                .style(tile_box(appearance));
 
-                row_element = row_element.push(item);
+            if is_focused {
+                item = item.style(focused_box(appearance));
             }
-        }
-
-        column_element = column_element.push(row_element);
-    }
 
-    column_element.into()
+            item.into()
+        }
+        Placement::Collapsed => iced::widget::container(text(title))
+            .padding(ui.tile_padding)
+            .max_height(ui.collapsed_row_height)
+            .width(width)
+            .clip(true)
+            .style(collapsed_box(appearance))
+            .into(),
+        Placement::Hidden => iced::widget::container(iced::widget::Column::new())
+            .padding(ui.tile_padding / 2.0)
+            .height(Length::Shrink)
+            .width(width)
+            .clip(true)
+            .style(collapsed_box(appearance))
+            .into(),
+    };
+
+    // Pressing and releasing over different tiles drags workspace to another place.
+    iced::widget::mouse_area(element)
+        .on_press(Message::TilePressed(id))
+        .on_release(Message::TileReleased(id))
+        .into()
 }
 
 pub fn tiled<'a>(
+    window: window::Id,
     tiling: &'a Tiling,
-    items: Vec<(Uid, String, iced::Element<'a, Message>)>,
+    items: Vec<TiledItem<'a>>,
     modal_item: Option<iced::Element<'a, Message>>,
     focused_id: Option<Uid>,
+    appearance: TileAppearance,
 ) -> iced::Element<'a, Message> {
     use iced::Length;
-    use iced::widget::{center, stack};
+    use iced::widget::{Column, Row, center, mouse_area, stack};
 
-    let items_len = items.len();
-    let mut items_iter = items.into_iter();
+    let focused_index = items.iter().position(|item| Some(item.0) == focused_id);
+    let layout = tiling.layout(items.len(), focused_index);
 
-    let mut column = iced::widget::Column::new()
+    let mut column = Column::new()
+        .spacing(appearance.ui.tile_gap)
         .height(Length::Fill)
         .width(Length::Fill);
 
     if tiling.fullscreen {
-        let item_container =
-            if let Some((_id, _title, element)) = items_iter.find(|i| Some(i.0) == focused_id) {
-                iced::widget::container(element)
-                    .padding(10)
-                    .height(Length::Fill)
-                    .width(Length::Fill)
-                    .style(focused_box)
-            } else {
-                iced::widget::container(text("No selection"))
-                    .padding(10)
-                    .height(Length::Fill)
-                    .width(Length::Fill)
-                    .style(focused_box)
-            };
+        let item_container = match focused_index {
+            Some(index) => {
+                let item = items.into_iter().nth(index).expect("focused item exists");
+                tile(Placement::Expanded, item, Length::Fill, true, appearance)
+            }
+            None => iced::widget::container(text("No selection"))
+                .padding(appearance.ui.tile_padding)
+                .height(Length::Fill)
+                .width(Length::Fill)
+                .style(focused_box(appearance))
+                .into(),
+        };
 
         column = column.push(item_container);
     } else {
-        let max_row_count = items_len.div_ceil(tiling.max_columns);
-
-        let expanded_rows_count = tiling.max_expanded_rows;
-        let top_collapsed_rows_count = tiling.top_expanded_row_index;
-        let bottom_collapsed_rows_count =
-            max_row_count.saturating_sub(top_collapsed_rows_count + expanded_rows_count);
-
-        if top_collapsed_rows_count > 0 {
-            let sub_column = collapsed_rows(
-                top_collapsed_rows_count,
-                tiling.max_columns,
-                &mut items_iter,
-                FoldingDirection::Up,
-            );
-
-            column = column.push(sub_column);
+        let top_row = tiling.top_row(items.len());
+        let expanded_rows = top_row..top_row + tiling.max_expanded_rows.max(1);
+
+        // Expanded tiles are separated by dividers instead of spacing.
+        let divider_size = appearance.ui.tile_gap.max(DIVIDER_SIZE);
+
+        // Rows are grouped into collapsed rows above, expanded rows and collapsed rows below.
+        let mut sections: [Vec<iced::Element<'a, Message>>; 3] = Default::default();
+        let mut rows: Vec<Row<'a, Message>> = Vec::new();
+
+        for (index, (placement, item)) in layout.into_iter().zip(items).enumerate() {
+            let is_expanded = placement.placement == Placement::Expanded;
+
+            if placement.column == 0 {
+                let row = if is_expanded {
+                    Row::new().height(Length::FillPortion(tiling.row_weight(placement.row)))
+                } else {
+                    Row::new().spacing(appearance.ui.tile_gap)
+                };
+                rows.push(row);
+            }
+
+            let mut row = rows.pop().expect("row is started at first column");
+            if is_expanded && placement.column > 0 {
+                row = row.push(divider(
+                    window,
+                    Divider::Column(placement.column - 1),
+                    divider_size,
+                ));
+            }
+
+            let width = Length::FillPortion(tiling.column_weight(placement.column));
+            let is_focused = focused_index == Some(index);
+            rows.push(row.push(tile(
+                placement.placement,
+                item,
+                width,
+                is_focused,
+                appearance,
+            )));
         }
 
-        if expanded_rows_count > 0 {
-            let sub_column = expanded_rows(
-                expanded_rows_count,
-                tiling.max_columns,
-                &mut items_iter,
-                focused_id,
-            );
+        for (row_index, row) in rows.into_iter().enumerate() {
+            let section = if row_index < expanded_rows.start {
+                0
+            } else if expanded_rows.contains(&row_index) {
+                if row_index > expanded_rows.start {
+                    sections[1].push(divider(window, Divider::Row(row_index - 1), divider_size));
+                }
+                1
+            } else {
+                2
+            };
 
-            column = column.push(sub_column);
+            sections[section].push(row.into());
         }
 
-        if bottom_collapsed_rows_count > 0 {
-            let sub_column = collapsed_rows(
-                bottom_collapsed_rows_count,
-                tiling.max_columns,
-                &mut items_iter,
-                FoldingDirection::Down,
-            );
+        let [top, expanded, bottom] = sections;
 
-            column = column.push(sub_column);
+        if !top.is_empty() {
+            column = column.push(Column::with_children(top).spacing(appearance.ui.tile_gap));
+        }
+        if !expanded.is_empty() {
+            column = column.push(Column::with_children(expanded).height(Length::Fill));
+        }
+        if !bottom.is_empty() {
+            column = column.push(Column::with_children(bottom).spacing(appearance.ui.tile_gap));
         }
     }
 
-    if let Some(modal_item) = modal_item {
-        stack![
-            iced::widget::container(column)
-                .width(Length::Fill)
-                .height(Length::Fill),
-            center(iced::widget::container(modal_item))
-        ]
-        .into()
-    } else {
+    let content = mouse_area(
         iced::widget::container(column)
             .width(Length::Fill)
-            .height(Length::Fill)
-            .into()
+            .height(Length::Fill),
+    )
+    .on_scroll(move |delta| Message::WheelScrolled(window, delta))
+    .on_move(move |position| Message::PointerMoved(window, position))
+    .on_release(Message::PointerReleased);
+
+    if let Some(modal_item) = modal_item {
+        stack![content, center(iced::widget::container(modal_item))].into()
+    } else {
+        content.into()
     }
 }
@@ -1,8 +1,81 @@
-use iced::Element;
+use std::collections::HashMap;
 
-use crate::{message::Message, state::workspace::Workspace};
+use iced::{Element, Length};
 
-pub fn workspace(_workspace: &Workspace) -> Element<Message> {
-    use iced::widget::text;
-    text("Workspace management is not implemented yet.").into()
+use crate::{
+    message::Message,
+    state::{Uid, activity::Activity, tiling::Placement, workspace::Workspace},
+};
+
+// Activities outside expanded rows only show their name.
+fn activity(activity: &Activity, placement: Placement, width: Length) -> Element<'_, Message> {
+    use iced::widget::{column, container, text};
+
+    let mut content = column!(text(&activity.name));
+    let height = match placement {
+        Placement::Expanded => {
+            if let Some(command) = &activity.command {
+                content = content.push(text(command).size(12));
+            }
+            Length::Fill
+        }
+        _ => Length::Shrink,
+    };
+
+    container(content)
+        .padding(5)
+        .width(width)
+        .height(height)
+        .style(container::bordered_box)
+        .into()
+}
+
+// Workspace name and root followed by its activities laid out by workspace tiling.
+pub fn workspace<'a>(
+    workspace: &'a Workspace,
+    activities: &'a HashMap<Uid, Activity>,
+) -> Element<'a, Message> {
+    use iced::widget::{Column, Row, column, container, text};
+
+    let mut header = column!(text(&workspace.name));
+    if let Some(root) = &workspace.root {
+        header = header.push(text(root.display().to_string()).size(12));
+    }
+
+    let tiling = &workspace.tiling;
+    let items: Vec<_> = workspace
+        .activity_ids
+        .iter()
+        .filter_map(|id| activities.get(id))
+        .collect();
+
+    let mut rows: Vec<Row<'a, Message>> = Vec::new();
+    for (placement, item) in tiling.layout(items.len(), None).into_iter().zip(items) {
+        if placement.placement == Placement::Hidden {
+            continue;
+        }
+
+        if placement.column == 0 {
+            let height = match placement.placement {
+                Placement::Expanded => Length::FillPortion(tiling.row_weight(placement.row)),
+                _ => Length::Shrink,
+            };
+            rows.push(Row::new().spacing(5).height(height));
+        }
+
+        let row = rows.pop().expect("row is started at first column");
+        let width = Length::FillPortion(tiling.column_weight(placement.column));
+        rows.push(row.push(activity(item, placement.placement, width)));
+    }
+
+    let rows = rows
+        .into_iter()
+        .fold(Column::new().spacing(5), |column, row| column.push(row));
+
+    container(column!(header, rows).spacing(10))
+        .padding(10)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .style(container::bordered_box)
+        .into()
 }
@@ -0,0 +1,128 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+
+use crate::cli::{IpcCommand, LaunchArgs, Query};
+use crate::ipc::protocol::{Request, Response};
+use crate::ipc::socket_path;
+
+// Sends a single request to a running instance and waits for its response.
+pub fn request(method: &str, params: Value) -> Result<Value> {
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path).with_context(|| {
+        format!(
+            "Cinnabar is not running (no control socket at {})",
+            path.display()
+        )
+    })?;
+
+    let mut line = serde_json::to_vec(&Request::new(1, method, params))?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+
+    let response: Response =
+        serde_json::from_str(&response).context("Invalid response from control socket")?;
+
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(anyhow!(error.message)),
+        (result, None) => Ok(result.unwrap_or(Value::Null)),
+    }
+}
+
+pub fn is_running() -> bool {
+    UnixStream::connect(socket_path()).is_ok()
+}
+
+// Hands launch arguments over to a running instance.
+pub fn forward(args: &LaunchArgs) -> ExitCode {
+    let result = serde_json::to_value(args)
+        .map_err(Into::into)
+        .and_then(|params| request("open", params));
+
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn run(command: IpcCommand) -> ExitCode {
+    let result = match command {
+        IpcCommand::Msg { handle, count } => {
+            request("run", json!({ "handle": handle, "count": count })).map(|_| ())
+        }
+        IpcCommand::Query { what, json } => request(what.method(), Value::Null).map(|result| {
+            if json {
+                println!("{result}");
+            } else {
+                print_query(what, &result);
+            }
+        }),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn as_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn print_query(what: Query, result: &Value) {
+    let items = result.as_array().map(Vec::as_slice).unwrap_or_default();
+
+    match what {
+        Query::Workspaces => {
+            for workspace in items {
+                let focused = workspace.get("focused").and_then(Value::as_bool) == Some(true);
+                println!(
+                    "{} {}\t{}",
+                    if focused { "*" } else { " " },
+                    as_str(workspace, "name"),
+                    as_str(workspace, "id")
+                );
+            }
+        }
+        Query::Focused => println!("{}", result.as_str().unwrap_or("-")),
+        Query::Mode => {
+            let stack = result
+                .get("stack")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>();
+            println!("{}", stack.join(" > "));
+        }
+        Query::Tiling => {
+            if let Some(tiling) = result.as_object() {
+                for (key, value) in tiling {
+                    println!("{key}: {value}");
+                }
+            }
+        }
+        Query::Commands => {
+            for command in items {
+                println!(
+                    "{:<28}{}",
+                    as_str(command, "handle"),
+                    as_str(command, "description")
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+pub mod client;
+pub mod protocol;
+pub mod server;
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{broadcast, oneshot};
+
+use self::protocol::{Event, Request, Response};
+
+pub const SOCKET_NAME: &str = "cinnabar.sock";
+
+// $XDG_RUNTIME_DIR/cinnabar.sock, falling back to a per-user directory in temp dir.
+pub fn socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join(SOCKET_NAME),
+        None => fallback_socket_dir().join(SOCKET_NAME),
+    }
+}
+
+// Temp dir is shared with other users, so the socket is kept in a directory only
+// its owner can enter, see `server::bind`.
+pub fn fallback_socket_dir() -> PathBuf {
+    let user = std::env::var("USER").unwrap_or_else(|_| "default".to_string());
+    std::env::temp_dir().join(format!("cinnabar-{user}"))
+}
+
+// Sends response of a single request back to its connection.
+//
+// Messages have to be cloneable, so the sender is shared and taken on first use.
+#[derive(Clone)]
+pub struct Responder(Arc<Mutex<Option<oneshot::Sender<Response>>>>);
+
+impl Responder {
+    pub fn new(sender: oneshot::Sender<Response>) -> Self {
+        Self(Arc::new(Mutex::new(Some(sender))))
+    }
+
+    pub fn respond(&self, response: Response) {
+        let sender = self.0.lock().ok().and_then(|mut sender| sender.take());
+
+        if let Some(sender) = sender {
+            let _ = sender.send(response);
+        }
+    }
+}
+
+impl fmt::Debug for Responder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Responder")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IpcRequest {
+    pub request: Request,
+    pub responder: Responder,
+}
+
+// Running server side of the control socket, used to broadcast events to subscribers.
+#[derive(Debug, Clone)]
+pub struct IpcHandle {
+    events: broadcast::Sender<Event>,
+}
+
+impl IpcHandle {
+    pub fn emit(&self, event: Event) {
+        // No subscribers is not an error.
+        let _ = self.events.send(event);
+    }
+
+    // Handle with a subscriber, without a running server.
+    #[cfg(test)]
+    pub fn subscribed() -> (Self, broadcast::Receiver<Event>) {
+        let (events, receiver) = broadcast::channel(16);
+        (Self { events }, receiver)
+    }
+}
@@ -0,0 +1,231 @@
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::Path;
+
+use iced::Subscription;
+use iced::futures::SinkExt;
+use iced::futures::Stream;
+use iced::futures::channel::mpsc;
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, oneshot};
+
+use crate::ipc::protocol::{
+    COMMAND_ERROR, Event, EventNotification, INVALID_REQUEST, JSONRPC_VERSION, PARSE_ERROR,
+    Request, Response,
+};
+use crate::ipc::{IpcHandle, IpcRequest, Responder, fallback_socket_dir, socket_path};
+use crate::message::Message;
+
+// Events buffered per subscriber before it starts lagging behind.
+const EVENT_BUFFER: usize = 64;
+
+pub fn subscription() -> Subscription<Message> {
+    Subscription::run(serve)
+}
+
+fn serve() -> impl Stream<Item = Message> {
+    iced::stream::channel(100, |mut output| async move {
+        let path = socket_path();
+
+        let listener = match bind(&path).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!(path = %path.display(), %err, "Failed to start control socket");
+                return;
+            }
+        };
+
+        tracing::info!(path = %path.display(), "Listening on control socket");
+
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+
+        let _ = output
+            .send(Message::IpcReady(IpcHandle {
+                events: events.clone(),
+            }))
+            .await;
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(connection(stream, output.clone(), events.clone()));
+                }
+                Err(err) => tracing::warn!(%err, "Failed to accept control socket connection"),
+            }
+        }
+    })
+}
+
+// Creates socket directory in temp dir, or checks that one created before is still private.
+//
+// Otherwise another user could create it first and squat on the socket.
+fn private_dir(dir: &Path) -> io::Result<()> {
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err),
+        _ => {}
+    }
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    // SAFETY: geteuid cannot fail and has no side effects.
+    let uid = unsafe { libc::geteuid() };
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory", dir.display()),
+        ));
+    }
+
+    Ok(())
+}
+
+// Binds the socket, replacing it when it's left over from an instance which is not running.
+//
+// Only the user running the application may connect to the socket.
+async fn bind(path: &Path) -> io::Result<UnixListener> {
+    let fallback_dir = fallback_socket_dir();
+    if path.parent() == Some(fallback_dir.as_path()) {
+        private_dir(&fallback_dir)?;
+    }
+
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "another instance is listening on the socket",
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+    Ok(listener)
+}
+
+// Requests and responses are newline delimited JSON-RPC messages.
+async fn connection(
+    stream: UnixStream,
+    mut output: mpsc::Sender<Message>,
+    events: broadcast::Sender<Event>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut subscription: Option<broadcast::Receiver<Event>> = None;
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let line = match line {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break,
+                    Err(err) => {
+                        tracing::debug!(%err, "Control socket connection failed");
+                        break;
+                    }
+                };
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let response = match serde_json::from_str::<Request>(&line) {
+                    Err(err) => Some(Response::error(None, PARSE_ERROR, err.to_string())),
+                    Ok(request) if request.jsonrpc != JSONRPC_VERSION => Some(Response::error(
+                        request.id,
+                        INVALID_REQUEST,
+                        format!("Unsupported JSON-RPC version {:?}", request.jsonrpc),
+                    )),
+                    // Notifications have no id and are never answered.
+                    Ok(request) if request.id.is_none() => {
+                        if request.method == "subscribe" {
+                            subscription = Some(events.subscribe());
+                        } else {
+                            dispatch(&mut output, request).await;
+                        }
+                        None
+                    }
+                    Ok(request) if request.method == "subscribe" => {
+                        subscription = Some(events.subscribe());
+                        Some(Response::ok(request.id, serde_json::Value::Bool(true)))
+                    }
+                    Ok(request) => Some(dispatch(&mut output, request).await),
+                };
+
+                if let Some(response) = response
+                    && write_line(&mut writer, &response).await.is_err()
+                {
+                    break;
+                }
+            }
+            event = next_event(&mut subscription) => match event {
+                Ok(event) => {
+                    if write_line(&mut writer, &EventNotification::from(event)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Control socket subscriber is lagging behind");
+                }
+                Err(broadcast::error::RecvError::Closed) => subscription = None,
+            },
+        }
+    }
+}
+
+// Hands request over to the application and waits for its response.
+async fn dispatch(output: &mut mpsc::Sender<Message>, request: Request) -> Response {
+    let id = request.id.clone();
+    let (sender, receiver) = oneshot::channel();
+
+    let message = Message::Ipc(IpcRequest {
+        request,
+        responder: Responder::new(sender),
+    });
+
+    if output.send(message).await.is_err() {
+        return Response::error(id, COMMAND_ERROR, "Application is shutting down");
+    }
+
+    receiver
+        .await
+        .unwrap_or_else(|_| Response::error(id, COMMAND_ERROR, "Request was dropped"))
+}
+
+async fn next_event(
+    subscription: &mut Option<broadcast::Receiver<Event>>,
+) -> Result<Event, broadcast::error::RecvError> {
+    match subscription {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn write_line(writer: &mut OwnedWriteHalf, message: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_dir_is_created_private() {
+        let dir =
+            std::env::temp_dir().join(format!("cinnabar-test-{}", crate::state::create_uid()));
+
+        private_dir(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().mode();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let shared = private_dir(&dir);
+        std::fs::remove_dir(&dir).unwrap();
+
+        assert_eq!(mode & 0o777, 0o700);
+        assert_eq!(shared.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+
+// Lines of the current session kept in memory for the log viewer.
+pub const SESSION_LOG_LINES: usize = 1000;
+
+// Rotated log files kept in the log directory.
+pub const MAX_LOG_FILES: usize = 7;
+
+static SESSION_LOG: OnceLock<SessionLog> = OnceLock::new();
+
+// In-memory tail of log lines written during current session.
+#[derive(Clone, Default)]
+pub struct SessionLog {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl SessionLog {
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+pub struct SessionLogWriter {
+    log: SessionLog,
+    buffer: Vec<u8>,
+}
+
+impl Write for SessionLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let text = String::from_utf8_lossy(&self.buffer).into_owned();
+        self.buffer.clear();
+
+        if let Ok(mut lines) = self.log.lines.lock() {
+            for line in text.lines() {
+                if lines.len() >= SESSION_LOG_LINES {
+                    lines.pop_front();
+                }
+                lines.push_back(line.to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SessionLogWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl<'a> MakeWriter<'a> for SessionLog {
+    type Writer = SessionLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        SessionLogWriter {
+            log: self.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+pub fn session_log() -> Option<&'static SessionLog> {
+    SESSION_LOG.get()
+}
+
+// $XDG_STATE_HOME/cinnabar, falling back to ~/.local/state/cinnabar
+pub fn log_dir() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+
+    Some(state_home.join("cinnabar"))
+}
+
+// Installs global subscriber writing to stderr, rotating log file and session log.
+//
+// Returned guard flushes the log file when dropped, so it must live until exit.
+pub fn init(level: LevelFilter) -> Option<WorkerGuard> {
+    // Dependencies (iced, wgpu) are only interesting when something goes wrong.
+    let filter = Targets::new()
+        .with_target(env!("CARGO_PKG_NAME"), level)
+        .with_default(LevelFilter::WARN.min(level));
+
+    let session_log = SESSION_LOG.get_or_init(SessionLog::default).clone();
+
+    let file_appender = log_dir().and_then(|dir| {
+        if let Err(err) = std::fs::create_dir_all(&dir) {
+            eprintln!("Failed to create log directory {}: {err}", dir.display());
+            return None;
+        }
+
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("cinnabar")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .inspect_err(|err| eprintln!("Failed to open log file: {err}"))
+            .ok()
+    });
+
+    let (file_layer, guard) = match file_appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_target(false)
+                .with_writer(session_log),
+        )
+        .init();
+
+    guard
+}
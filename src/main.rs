@@ -1,16 +1,121 @@
 mod app;
+mod cli;
 mod config;
 mod elements;
+mod ipc;
+mod logging;
 mod message;
 mod state;
+#[cfg(test)]
+mod testing;
+
+use std::process::ExitCode;
 
 use app::App;
-use iced::Font;
+use clap::Parser;
+use config::Config;
+
+fn main() -> ExitCode {
+    state::status_bar::init_local_offset();
+
+    let cli = cli::Cli::parse();
+
+    match cli.command {
+        Some(cli::CliCommand::CheckConfig { config }) => {
+            return check_config(config.unwrap_or_else(|| state::CONFIG_PATH.into()));
+        }
+        Some(cli::CliCommand::Ipc(command)) => return ipc::client::run(command),
+        None => {}
+    }
+
+    let mut launch = cli.launch;
+
+    // Running instance has its own working directory.
+    if let Some(directory) = &launch.directory {
+        match directory.canonicalize() {
+            Ok(directory) => launch.directory = Some(directory),
+            Err(err) => {
+                eprintln!("Error: {}: {err}", directory.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if !cli.new_instance && ipc::client::is_running() {
+        return ipc::client::forward(&launch);
+    }
+
+    let _log_guard = logging::init(cli.log_level);
+
+    match run(launch) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            tracing::error!(%err, "Application failed");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(launch: cli::LaunchArgs) -> iced::Result {
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "Starting Cinnabar");
+
+    let config = Config::from_toml(state::CONFIG_PATH);
 
-fn main() -> iced::Result {
-    iced::application("Cinnabar", App::update, App::view)
+    // Daemon keeps running while screens are opened and closed, each in its own window.
+    iced::daemon(App::title, App::update, App::view)
         .theme(App::theme)
         .subscription(App::subscription)
-        .default_font(Font::with_name("Iosevka Nerd Font"))
-        .run_with(App::new)
+        .scale_factor(App::scale_factor)
+        .settings(iced::Settings {
+            default_font: config.ui.font,
+            default_text_size: config.ui.font_size.into(),
+            ..iced::Settings::default()
+        })
+        .run_with(move || App::new(config, launch))
+}
+
+// Loads commands the same way as on launch, without opening windows or workspaces.
+fn check_config(path: std::path::PathBuf) -> ExitCode {
+    use state::keymap::check_keymaps;
+    use state::notification::Severity;
+    use state::template::TemplateError;
+
+    let config = match Config::try_from_toml(path) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let app = App::load(config);
+
+    // Startup workspaces are not created, only their templates are looked up.
+    let load_errors: Vec<_> = app
+        .notifications
+        .history()
+        .filter(|notification| notification.severity == Severity::Error)
+        .map(|notification| notification.message.clone())
+        .chain(
+            app.startup
+                .workspaces
+                .iter()
+                .filter(|name| !app.templates.iter().any(|template| template.name == **name))
+                .map(|name| TemplateError::NotFound { name: name.clone() }.to_string()),
+        )
+        .collect();
+    let issues = check_keymaps(&app.keymaps, &app.mode_keymaps, &app.commands);
+
+    for message in &load_errors {
+        println!("error: {message}");
+    }
+    for issue in &issues {
+        let level = if issue.is_error() { "error" } else { "warning" };
+        println!("{level}: {issue}");
+    }
+
+    if load_errors.is_empty() && !issues.iter().any(|issue| issue.is_error()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }
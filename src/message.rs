@@ -1,9 +1,33 @@
 use iced::keyboard::{Key, Modifiers};
+use iced::mouse::ScrollDelta;
+use iced::{Point, window};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::ipc::{IpcHandle, IpcRequest};
+use crate::state::Uid;
+use crate::state::tiling::Divider;
+
+#[derive(Debug, Clone)]
 pub enum Message {
     KeyPress { key: Key, modifiers: Modifiers },
+    KeyRelease { key: Key, modifiers: Modifiers },
+    // Time to check whether a held key runs its hold binding.
+    HoldCheck,
     Command(String),
+    TilePressed(Uid),
+    TileReleased(Uid),
+    WheelScrolled(window::Id, ScrollDelta),
+    DividerPressed(window::Id, Divider),
+    PointerMoved(window::Id, Point),
+    PointerReleased,
+    // Mouse released or left a window anywhere, not only over tiles.
+    DividerReleased,
+    WindowClosed(window::Id),
+    WindowFocused(window::Id),
+    Tick,
+    ShellSegmentOutput { command: String, output: String },
+    SystemThemeDetected { dark: bool },
+    IpcReady(IpcHandle),
+    Ipc(IpcRequest),
     // TODO: Refactor out into commands
     ToggleModal,
 }
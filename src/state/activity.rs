@@ -0,0 +1,20 @@
+use crate::state::{Uid, create_uid};
+
+// Something a workspace is used for, e.g. an editor or a test runner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    pub id: Uid,
+    pub name: String,
+    // Shell command the activity runs, if any.
+    pub command: Option<String>,
+}
+
+impl Activity {
+    pub fn new(name: impl Into<String>, command: Option<String>) -> Self {
+        Self {
+            id: create_uid(),
+            name: name.into(),
+            command,
+        }
+    }
+}
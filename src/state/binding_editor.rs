@@ -0,0 +1,605 @@
+use anyhow::Result;
+use iced::Task;
+use iced::keyboard::{Key, Modifiers, key::Named};
+use std::fmt;
+use thiserror::Error;
+
+use crate::config::{
+    edit_config, edit_table, format_keybind, format_trigger, parse_keybind, parse_trigger,
+};
+use crate::message::Message;
+use crate::state::{
+    State,
+    command::CommandMap,
+    keymap::{
+        GLOBAL_KEYMAP, Keymap, KeymapNode, Keymaps, Trigger, bound_command, bound_commands,
+        inherits_from, resolve_inherited,
+    },
+    mode::{Mode, ModeActions},
+    notification::NotificationActions,
+};
+
+#[derive(Error, Debug)]
+pub enum BindingEditorError {
+    #[error("Binding editor is not open")]
+    NotOpen,
+    #[error("No rebinding is waiting for confirmation")]
+    NothingPending,
+    #[error("Key {keybind} cannot be written to configuration")]
+    UnsupportedKey { keybind: String },
+    #[error("Configuration was not loaded from a file")]
+    NoConfigFile,
+}
+
+// Existing bindings affected by binding a key to a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeybindConflict {
+    // Bound in the edited keymap, loses the key.
+    Replaces { handle: String },
+    // Inherited by the edited keymap, no longer reachable from it.
+    Shadows { handle: String },
+    // Bound in a keymap extending the edited one, keeps the key there.
+    OverriddenBy { keymap: String, handle: String },
+}
+
+impl fmt::Display for KeybindConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Replaces { handle } => write!(f, "replaces {handle}"),
+            Self::Shadows { handle } => write!(f, "shadows inherited {handle}"),
+            Self::OverriddenBy { keymap, handle } => {
+                write!(f, "is overridden by {handle} in {keymap}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingRow {
+    pub handle: String,
+    pub name: String,
+    // Formatted as `keymap: keybind`.
+    pub bindings: Vec<String>,
+}
+
+// Captured key which conflicts with existing bindings, applied once confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingBinding {
+    pub keybind: (Key, Modifiers),
+    pub conflicts: Vec<KeybindConflict>,
+}
+
+// Every command with its bindings, shown while in binding mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingEditor {
+    pub rows: Vec<BindingRow>,
+    pub selected: usize,
+    // Keymap new bindings are written to.
+    pub keymap: String,
+    pub pending: Option<PendingBinding>,
+}
+
+impl BindingEditor {
+    pub fn selected_row(&self) -> Option<&BindingRow> {
+        self.rows.get(self.selected)
+    }
+}
+
+fn binding_rows(keymaps: &Keymaps, commands: &CommandMap) -> Vec<BindingRow> {
+    let mut keymaps: Vec<&Keymap> = keymaps.values().collect();
+    keymaps.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut rows: Vec<BindingRow> = commands
+        .command_values()
+        .map(|command| BindingRow {
+            handle: command.handle().to_string(),
+            name: command.name().to_string(),
+            bindings: keymaps
+                .iter()
+                .flat_map(|keymap| {
+                    bound_commands(keymap)
+                        .map(|(keybind, handle)| (Trigger::Press, keybind, handle))
+                        .chain(
+                            keymap
+                                .triggered
+                                .iter()
+                                .map(|((trigger, keybind), handle)| (*trigger, keybind, handle)),
+                        )
+                        .filter(|(_, _, handle)| *handle == command.handle())
+                        .map(|(trigger, keybind, _)| {
+                            format!("{}: {}", keymap.name, format_trigger(trigger, keybind))
+                        })
+                })
+                .collect(),
+        })
+        .collect();
+
+    rows.sort_by(|a, b| a.handle.cmp(&b.handle));
+    rows
+}
+
+pub fn keybind_conflicts(
+    keymaps: &Keymaps,
+    name: &str,
+    handle: &str,
+    keybind: &(Key, Modifiers),
+) -> Vec<KeybindConflict> {
+    let mut conflicts = Vec::new();
+
+    if let Some(keymap) = keymaps.get(name) {
+        if let Some(bound) = bound_command(keymap, keybind).filter(|bound| *bound != handle) {
+            conflicts.push(KeybindConflict::Replaces {
+                handle: bound.clone(),
+            });
+        }
+        if let Some(inherited) =
+            resolve_inherited(keymaps, keymap, keybind).filter(|inherited| *inherited != handle)
+        {
+            conflicts.push(KeybindConflict::Shadows {
+                handle: inherited.clone(),
+            });
+        }
+    }
+
+    let mut descendants: Vec<&Keymap> = keymaps
+        .values()
+        .filter(|keymap| inherits_from(keymaps, &keymap.name, name))
+        .collect();
+    descendants.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for keymap in descendants {
+        if let Some(bound) = bound_command(keymap, keybind).filter(|bound| *bound != handle) {
+            conflicts.push(KeybindConflict::OverriddenBy {
+                keymap: keymap.name.clone(),
+                handle: bound.clone(),
+            });
+        }
+    }
+
+    conflicts
+}
+
+// Editor commands only work while it is shown and not capturing a key.
+fn open_editor(state: &mut State) -> Result<&mut BindingEditor, BindingEditorError> {
+    state
+        .binding_editor
+        .as_mut()
+        .filter(|_| state.mode == Mode::Binding)
+        .ok_or(BindingEditorError::NotOpen)
+}
+
+// Pressing only a modifier does not finish the keybind.
+fn is_modifier(key: &Key) -> bool {
+    matches!(
+        key,
+        Key::Named(Named::Shift | Named::Control | Named::Alt | Named::Super | Named::Meta)
+    )
+}
+
+pub trait BindingEditorActions {
+    fn open_binding_editor(&mut self);
+    fn binding_editor_select(&mut self, offset: isize) -> Result<()>;
+    fn binding_editor_next_keymap(&mut self) -> Result<()>;
+    fn start_key_capture(&mut self) -> Result<()>;
+    fn key_captured(&mut self, keybind: (Key, Modifiers)) -> Result<()>;
+    fn confirm_binding(&mut self) -> Result<()>;
+    fn set_keybind(&mut self, name: &str, handle: &str, keybind: (Key, Modifiers)) -> Result<()>;
+    fn capture_key_pressed(
+        &mut self,
+        key: &Key,
+        modifiers: Modifiers,
+    ) -> Option<Result<Task<Message>>>;
+    fn active_binding_editor(&self) -> Option<&BindingEditor>;
+}
+
+impl BindingEditorActions for State {
+    fn open_binding_editor(&mut self) {
+        self.binding_editor = Some(BindingEditor {
+            rows: binding_rows(&self.keymaps, &self.commands),
+            selected: 0,
+            keymap: GLOBAL_KEYMAP.to_string(),
+            pending: None,
+        });
+        self.push_mode(Mode::Binding);
+    }
+
+    fn binding_editor_select(&mut self, offset: isize) -> Result<()> {
+        let editor = open_editor(self)?;
+
+        if !editor.rows.is_empty() {
+            let len = editor.rows.len() as isize;
+            editor.selected = (editor.selected as isize + offset).rem_euclid(len) as usize;
+        }
+        editor.pending = None;
+
+        Ok(())
+    }
+
+    fn binding_editor_next_keymap(&mut self) -> Result<()> {
+        let mut names: Vec<String> = self.keymaps.keys().cloned().collect();
+        names.sort();
+        let editor = open_editor(self)?;
+
+        let next = names
+            .iter()
+            .position(|name| *name == editor.keymap)
+            .map_or(0, |index| (index + 1) % names.len());
+
+        if let Some(name) = names.into_iter().nth(next) {
+            editor.keymap = name;
+        }
+        editor.pending = None;
+
+        Ok(())
+    }
+
+    fn start_key_capture(&mut self) -> Result<()> {
+        let editor = open_editor(self)?;
+
+        editor.pending = None;
+        self.push_mode(Mode::KeyCapture);
+        Ok(())
+    }
+
+    // Binds right away unless it would change what other keys do.
+    fn key_captured(&mut self, keybind: (Key, Modifiers)) -> Result<()> {
+        self.pop_mode();
+
+        let formatted = format_keybind(&keybind);
+        if parse_keybind(formatted.split('-').collect()) != keybind {
+            return Err(BindingEditorError::UnsupportedKey { keybind: formatted }.into());
+        }
+
+        let editor = self
+            .binding_editor
+            .as_ref()
+            .ok_or(BindingEditorError::NotOpen)?;
+        let Some(row) = editor.selected_row() else {
+            return Ok(());
+        };
+
+        let conflicts = keybind_conflicts(&self.keymaps, &editor.keymap, &row.handle, &keybind);
+        if conflicts.is_empty() {
+            let (keymap, handle) = (editor.keymap.clone(), row.handle.clone());
+            return self.set_keybind(&keymap, &handle, keybind);
+        }
+
+        if let Some(editor) = self.binding_editor.as_mut() {
+            editor.pending = Some(PendingBinding { keybind, conflicts });
+        }
+        Ok(())
+    }
+
+    fn confirm_binding(&mut self) -> Result<()> {
+        let editor = open_editor(self)?;
+        let pending = editor
+            .pending
+            .take()
+            .ok_or(BindingEditorError::NothingPending)?;
+        let Some(row) = editor.selected_row() else {
+            return Ok(());
+        };
+
+        let (keymap, handle) = (editor.keymap.clone(), row.handle.clone());
+        self.set_keybind(&keymap, &handle, pending.keybind)
+    }
+
+    // Configuration file is written first, so keymaps never differ from it.
+    // Command keeps a single key per keymap, as in configuration.
+    fn set_keybind(&mut self, name: &str, handle: &str, keybind: (Key, Modifiers)) -> Result<()> {
+        let path = self
+            .config_path
+            .as_ref()
+            .ok_or(BindingEditorError::NoConfigFile)?;
+        let formatted = format_keybind(&keybind);
+
+        edit_config(path, |document| {
+            let keymaps = edit_table(document.as_table_mut(), "keymaps")?;
+            let keymap = edit_table(keymaps, name)?;
+
+            // Drop every other command bound to the same key, however it is written.
+            let taken: Vec<String> = keymap
+                .iter()
+                .filter(|(key, value)| {
+                    !matches!(*key, "extends" | "unbind")
+                        && value.as_str().is_some_and(|value| {
+                            parse_trigger(value.split('-').collect())
+                                == (Trigger::Press, keybind.clone())
+                        })
+                })
+                .map(|(key, _)| key.to_string())
+                .collect();
+            for key in taken {
+                keymap.remove(&key);
+            }
+
+            keymap.insert(handle, toml_edit::value(formatted.clone()));
+            Ok(())
+        })?;
+
+        let keymap = self
+            .keymaps
+            .entry(name.to_string())
+            .or_insert_with(|| Keymap::new(name.to_string()));
+        keymap
+            .mapping
+            .retain(|_, node| !matches!(node, KeymapNode::Command(bound) if bound == handle));
+        keymap.overridden.remove(&(Trigger::Press, keybind.clone()));
+        keymap
+            .mapping
+            .insert(keybind, KeymapNode::Command(handle.to_string()));
+
+        if let Some(editor) = self.binding_editor.as_mut() {
+            editor.rows = binding_rows(&self.keymaps, &self.commands);
+        }
+        self.push_info(format!("Bound {handle} to {formatted} in {name}"));
+        Ok(())
+    }
+
+    // Every key goes to the editor while capturing, except `esc` which cancels.
+    fn capture_key_pressed(
+        &mut self,
+        key: &Key,
+        modifiers: Modifiers,
+    ) -> Option<Result<Task<Message>>> {
+        if self.mode != Mode::KeyCapture {
+            return None;
+        }
+        if is_modifier(key) {
+            return Some(Ok(Task::none()));
+        }
+
+        if *key == Key::Named(Named::Escape) && modifiers.is_empty() {
+            self.pop_mode();
+            return Some(Ok(Task::none()));
+        }
+
+        Some(
+            self.key_captured((key.clone(), modifiers))
+                .map(|_| Task::none()),
+        )
+    }
+
+    // Editor stays around after its mode is popped, but is no longer shown.
+    fn active_binding_editor(&self) -> Option<&BindingEditor> {
+        self.binding_editor
+            .as_ref()
+            .filter(|_| matches!(self.mode, Mode::Binding | Mode::KeyCapture))
+    }
+}
+
+pub fn binding_editor_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_navigation_command(
+        "binding-editor",
+        "Keybindings",
+        "List commands with their keybindings to change them",
+        |state: &mut State| {
+            state.open_binding_editor();
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "binding-editor-next",
+        "Next Command",
+        "Select next command in binding editor",
+        |state: &mut State| {
+            state.binding_editor_select(1)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "binding-editor-previous",
+        "Previous Command",
+        "Select previous command in binding editor",
+        |state: &mut State| {
+            state.binding_editor_select(-1)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "binding-editor-keymap",
+        "Next Keymap",
+        "Choose next keymap to write bindings to",
+        |state: &mut State| {
+            state.binding_editor_next_keymap()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "binding-editor-rebind",
+        "Rebind",
+        "Press a key to bind to selected command",
+        |state: &mut State| {
+            state.start_key_capture()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "binding-editor-confirm",
+        "Confirm Rebind",
+        "Bind captured key despite conflicts",
+        |state: &mut State| {
+            state.confirm_binding()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::state::keymap::resolve_in;
+    use crate::testing::{Harness, TEST_CONFIG, TempConfig};
+
+    fn with_config_file() -> (Harness, TempConfig) {
+        Harness::with_config_file(&format!("# keep me\n{TEST_CONFIG}"))
+    }
+
+    fn open_at(harness: &mut Harness, handle: &str) {
+        harness.press("cmd-b");
+        let editor = harness.app.binding_editor.as_mut().unwrap();
+        editor.selected = editor
+            .rows
+            .iter()
+            .position(|row| row.handle == handle)
+            .unwrap();
+    }
+
+    fn resolve(harness: &Harness, keymap: &str, keybind: &str) -> Option<String> {
+        resolve_in(
+            &harness.app.keymaps,
+            keymap,
+            &parse_keybind(keybind.split('-').collect()),
+        )
+        .cloned()
+    }
+
+    #[test]
+    fn rows_list_bindings_of_every_command() {
+        let mut harness = Harness::new();
+
+        open_at(&mut harness, "workspace-create");
+
+        let editor = harness.app.active_binding_editor().unwrap();
+        assert_eq!(
+            editor.rows.len(),
+            harness.app.commands.command_values().count()
+        );
+        assert_eq!(
+            editor.selected_row().unwrap().bindings,
+            vec!["workspace-mode: c"]
+        );
+    }
+
+    #[test]
+    fn captured_key_is_bound_and_written() {
+        let (mut harness, config) = with_config_file();
+
+        open_at(&mut harness, "workspace-create");
+        harness.press("enter");
+        // Modifier pressed on its own keeps capturing.
+        let _ = harness.app.update(Message::KeyPress {
+            key: Key::Named(Named::Shift),
+            modifiers: Modifiers::SHIFT,
+        });
+        assert_eq!(harness.mode(), &Mode::KeyCapture);
+        harness.press("cmd-shift-j");
+
+        assert_eq!(harness.mode(), &Mode::Binding);
+        assert_eq!(
+            resolve(&harness, GLOBAL_KEYMAP, "cmd-shift-j").as_deref(),
+            Some("workspace-create")
+        );
+
+        let content = config.read();
+        assert!(content.contains("# keep me"));
+        let keymaps = Config::parse(&content).keymaps;
+        assert_eq!(
+            keymaps[GLOBAL_KEYMAP].mapping[&parse_keybind(vec!["cmd", "shift", "j"])],
+            KeymapNode::Command("workspace-create".to_string())
+        );
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn conflicting_key_waits_for_confirmation() {
+        let (mut harness, config) = with_config_file();
+
+        open_at(&mut harness, "workspace-create");
+        harness.press_all(&["enter", "cmd-w"]);
+
+        let pending = harness.app.binding_editor.as_ref().unwrap().pending.clone();
+        assert_eq!(
+            pending.unwrap().conflicts,
+            vec![KeybindConflict::Replaces {
+                handle: "workspace-mode".to_string()
+            }]
+        );
+        assert_eq!(
+            resolve(&harness, GLOBAL_KEYMAP, "cmd-w").as_deref(),
+            Some("workspace-mode")
+        );
+
+        harness.press("y");
+
+        assert_eq!(
+            resolve(&harness, GLOBAL_KEYMAP, "cmd-w").as_deref(),
+            Some("workspace-create")
+        );
+        let keymaps = Config::parse(&config.read()).keymaps;
+        assert!(keymaps[GLOBAL_KEYMAP].overridden.is_empty());
+        assert!(
+            !bound_commands(&keymaps[GLOBAL_KEYMAP]).any(|(_, handle)| handle == "workspace-mode")
+        );
+    }
+
+    #[test]
+    fn escape_cancels_capture() {
+        let (mut harness, _config) = with_config_file();
+
+        open_at(&mut harness, "workspace-create");
+        harness.press_all(&["enter", "esc"]);
+
+        assert_eq!(harness.mode(), &Mode::Binding);
+        assert_eq!(
+            resolve(&harness, GLOBAL_KEYMAP, "esc").as_deref(),
+            Some("pop-mode")
+        );
+
+        harness.press("esc");
+
+        assert!(harness.app.active_binding_editor().is_none());
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn conflicts_include_inherited_and_extending_keymaps() {
+        let harness = Harness::new();
+        let keymaps = &harness.app.keymaps;
+
+        assert_eq!(
+            keybind_conflicts(
+                keymaps,
+                GLOBAL_KEYMAP,
+                "view-mode",
+                &parse_keybind(vec!["c"])
+            ),
+            vec![KeybindConflict::OverriddenBy {
+                keymap: "workspace-mode".to_string(),
+                handle: "workspace-create".to_string(),
+            }]
+        );
+        assert_eq!(
+            keybind_conflicts(
+                keymaps,
+                "workspace-mode",
+                "view-mode",
+                &parse_keybind(vec!["esc"])
+            ),
+            vec![KeybindConflict::Shadows {
+                handle: "pop-mode".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn binding_requires_config_file() {
+        let mut harness = Harness::new();
+
+        open_at(&mut harness, "workspace-create");
+        harness.press_all(&["enter", "cmd-j"]);
+
+        assert_eq!(harness.errors(), 1);
+        assert_eq!(resolve(&harness, GLOBAL_KEYMAP, "cmd-j"), None);
+    }
+}
@@ -1,37 +1,137 @@
 use anyhow::Result;
 use std::collections::HashMap;
+use thiserror::Error;
 
 use iced::Task;
 
 use crate::{
+    app::AppError,
+    ipc::protocol::Event,
     message::Message,
-    state::{State, mode::ModeActions},
+    state::{
+        State,
+        ipc::IpcActions,
+        macros::{MacroActions, saved_register},
+        mode::ModeActions,
+        notification::NotificationActions,
+        screen::TransientTool,
+        script::ScriptActions,
+    },
 };
 
-pub type Action = fn(&mut State) -> Result<Task<Message>>;
+// Commands may run other commands, e.g. composites, macros and scripts.
+const MAX_NESTING: usize = 8;
 
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("Command with handle {handle:?} already exists")]
+    HandleTaken { handle: String },
+    #[error("Command {handle:?} runs unknown command {unknown:?}")]
+    UnknownStep { handle: String, unknown: String },
+    #[error("Command {handle:?} runs invalid command {invalid:?}")]
+    InvalidStep { handle: String, invalid: String },
+    #[error("Command {handle:?} has no steps")]
+    NoSteps { handle: String },
+    #[error("Commands are nested deeper than {MAX_NESTING} levels")]
+    TooDeep,
+    #[error("No command to repeat")]
+    NothingToRepeat,
+}
+
+pub type BuiltinAction = fn(&mut State) -> Result<Task<Message>>;
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    Builtin(BuiltinAction),
+    // Runs the script loaded under the command handle.
+    Script,
+    Macro(char),
+    // Runs other commands in order.
+    Composite(Vec<String>),
+}
+
+// Command defined in config as a sequence of other commands.
 #[derive(Debug, Clone, PartialEq)]
+pub struct CompositeConfig {
+    pub handle: String,
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct Command {
     handle: String,
     name: String,
     description: String,
     action: Action,
+    // Whether command interprets count typed before it, others ignore the count.
+    accepts_count: bool,
+    // Navigational commands, e.g. mode changes, are not repeated by `repeat-last-command`.
+    repeatable: bool,
+}
+
+// Last repeatable command run directly, along with the count it was given.
+#[derive(Debug, Clone)]
+pub struct LastCommand {
+    pub command: Command,
+    pub count: Option<usize>,
 }
 
 impl Command {
-    pub fn run(&self, state: &mut State) -> Task<Message> {
-        match (self.action)(state) {
-            Ok(task) => task,
-            Err(err) => {
-                state.push_error(err);
-                Task::none()
-            }
+    pub fn new<S: Into<String>>(handle: S, name: S, description: S, action: Action) -> Self {
+        Self {
+            handle: handle.into(),
+            name: name.into(),
+            description: description.into(),
+            action,
+            accepts_count: false,
+            repeatable: true,
+        }
+    }
+
+    pub fn with_count(self) -> Self {
+        Self {
+            accepts_count: true,
+            ..self
+        }
+    }
+
+    pub fn without_repeat(self) -> Self {
+        Self {
+            repeatable: false,
+            ..self
+        }
+    }
+
+    pub fn accepts_count(&self) -> bool {
+        self.accepts_count
+    }
+
+    pub fn is_repeatable(&self) -> bool {
+        self.repeatable
+    }
+
+    pub fn run(&self, state: &mut State) -> Result<Task<Message>> {
+        match &self.action {
+            Action::Builtin(action) => action(state),
+            Action::Script => state.run_script(&self.handle),
+            Action::Macro(register) => state.replay_macro(*register, state.command_count()),
+            Action::Composite(steps) => state.run_commands(steps),
         }
     }
 
     pub fn handle(&self) -> &str {
         &self.handle
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
 }
 
 pub struct CommandMap {
@@ -54,14 +154,39 @@ impl CommandMap {
         handle: S,
         name: S,
         description: S,
-        action: Action,
+        action: BuiltinAction,
     ) {
-        let command = Command {
-            handle: handle.into(),
-            name: name.into(),
-            description: description.into(),
-            action,
-        };
+        self.insert(Command::new(
+            handle,
+            name,
+            description,
+            Action::Builtin(action),
+        ));
+    }
+
+    pub fn insert_counted_command<S: Into<String>>(
+        &mut self,
+        handle: S,
+        name: S,
+        description: S,
+        action: BuiltinAction,
+    ) {
+        self.insert(Command::new(handle, name, description, Action::Builtin(action)).with_count());
+    }
+
+    pub fn insert_navigation_command<S: Into<String>>(
+        &mut self,
+        handle: S,
+        name: S,
+        description: S,
+        action: BuiltinAction,
+    ) {
+        self.insert(
+            Command::new(handle, name, description, Action::Builtin(action)).without_repeat(),
+        );
+    }
+
+    pub fn insert(&mut self, command: Command) {
         self.commands.insert(command.handle.clone(), command);
     }
 
@@ -72,8 +197,18 @@ impl CommandMap {
 
 pub trait CommandActions {
     fn resolve_command(&self, handle: &str) -> Option<Command>;
+    fn run_command(&mut self, handle: &str) -> Result<Task<Message>>;
+    fn run_command_with_count(
+        &mut self,
+        handle: &str,
+        count: Option<usize>,
+    ) -> Result<Task<Message>>;
+    fn command_count(&self) -> usize;
+    fn repeat_last_command(&mut self) -> Result<Task<Message>>;
+    fn run_commands(&mut self, handles: &[String]) -> Result<Task<Message>>;
     fn insert_command(&mut self, command: Command);
     fn merge_commands(&mut self, commands: CommandMap);
+    fn load_composite_commands(&mut self, composites: Vec<CompositeConfig>);
 }
 
 impl CommandActions for State {
@@ -81,23 +216,161 @@ impl CommandActions for State {
         self.commands.get_command(handle).clone()
     }
 
+    fn run_command(&mut self, handle: &str) -> Result<Task<Message>> {
+        self.run_command_with_count(handle, None)
+    }
+
+    fn run_command_with_count(
+        &mut self,
+        handle: &str,
+        count: Option<usize>,
+    ) -> Result<Task<Message>> {
+        let _span = tracing::info_span!("command", handle = %handle, ?count).entered();
+
+        let command = self
+            .resolve_command(handle)
+            .ok_or_else(|| AppError::CommandNotFound {
+                handle: handle.to_string(),
+            })?;
+
+        if self.command_depth >= MAX_NESTING {
+            return Err(CommandError::TooDeep.into());
+        }
+
+        if count.is_some() && !command.accepts_count() {
+            tracing::debug!("Command ignores count");
+        }
+        let count = count.filter(|_| command.accepts_count());
+
+        tracing::debug!(mode = ?self.mode, "Dispatching command");
+        // Commands run by other commands are repeated and reported along with them.
+        let top_level = self.command_depth == 0;
+        if top_level && command.is_repeatable() {
+            self.last_command = Some(LastCommand {
+                command: command.clone(),
+                count,
+            });
+        }
+
+        // Commands run from this one get their own count.
+        let outer_count = std::mem::replace(&mut self.count, count);
+        self.command_depth += 1;
+        let result = command.run(self);
+        self.command_depth -= 1;
+        self.count = outer_count;
+
+        if top_level {
+            self.emit_ipc_event(Event::CommandExecuted {
+                handle: handle.to_string(),
+                error: result.as_ref().err().map(|err| err.to_string()),
+            });
+        }
+
+        result
+    }
+
+    // Count given to the running command, one when none was typed.
+    fn command_count(&self) -> usize {
+        self.count.unwrap_or(1)
+    }
+
+    // Count typed before repeating replaces the original one.
+    fn repeat_last_command(&mut self) -> Result<Task<Message>> {
+        let last = self
+            .last_command
+            .clone()
+            .ok_or(CommandError::NothingToRepeat)?;
+
+        self.run_command_with_count(last.command.handle(), self.count.or(last.count))
+    }
+
+    // First failing command stops the rest.
+    fn run_commands(&mut self, handles: &[String]) -> Result<Task<Message>> {
+        let tasks = handles
+            .iter()
+            .map(|handle| self.run_command(handle))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Task::batch(tasks))
+    }
+
     fn insert_command(&mut self, command: Command) {
-        self.commands.insert_command(
-            command.handle,
-            command.name,
-            command.description,
-            command.action,
-        );
+        self.commands.insert(command);
     }
 
     fn merge_commands(&mut self, commands: CommandMap) {
-        for command in commands.command_values() {
-            self.commands.insert_command(
-                command.handle.clone(),
-                command.name.clone(),
-                command.description.clone(),
-                command.action,
-            );
+        for (_, command) in commands.commands {
+            self.commands.insert(command);
+        }
+    }
+
+    // Composites may refer to each other, so steps are checked once all of them are known.
+    // Invalid ones are reported and skipped.
+    fn load_composite_commands(&mut self, composites: Vec<CompositeConfig>) {
+        let handles: Vec<_> = composites
+            .iter()
+            .map(|composite| composite.handle.clone())
+            .collect();
+
+        let mut errors: Vec<_> = composites
+            .iter()
+            .map(|composite| {
+                let unknown = composite
+                    .steps
+                    .iter()
+                    .find(|step| !handles.contains(step) && self.resolve_command(step).is_none());
+
+                if self.resolve_command(&composite.handle).is_some() {
+                    Some(CommandError::HandleTaken {
+                        handle: composite.handle.clone(),
+                    })
+                } else if composite.steps.is_empty() {
+                    Some(CommandError::NoSteps {
+                        handle: composite.handle.clone(),
+                    })
+                } else {
+                    unknown.map(|unknown| CommandError::UnknownStep {
+                        handle: composite.handle.clone(),
+                        unknown: unknown.clone(),
+                    })
+                }
+            })
+            .collect();
+
+        // Commands running an invalid composite are invalid as well.
+        while let Some((index, invalid)) =
+            composites
+                .iter()
+                .enumerate()
+                .find_map(|(index, composite)| {
+                    let invalid = composite.steps.iter().find(|step| {
+                        self.resolve_command(step).is_none()
+                            && handles
+                                .iter()
+                                .zip(&errors)
+                                .any(|(handle, error)| handle == *step && error.is_some())
+                    })?;
+                    errors[index].is_none().then(|| (index, invalid.clone()))
+                })
+        {
+            errors[index] = Some(CommandError::InvalidStep {
+                handle: composites[index].handle.clone(),
+                invalid,
+            });
+        }
+
+        // Saved macros go back into their register, taken registers are reported above.
+        for (composite, error) in composites.into_iter().zip(errors) {
+            match (error, saved_register(&composite.handle)) {
+                (Some(error), _) => self.push_error(error.into()),
+                (None, Some(register)) => self.set_macro(register, composite.steps),
+                (None, None) => self.insert_command(Command::new(
+                    composite.handle,
+                    composite.name,
+                    composite.description,
+                    Action::Composite(composite.steps),
+                )),
+            }
         }
     }
 }
@@ -105,7 +378,7 @@ impl CommandActions for State {
 pub fn global_commands() -> CommandMap {
     let mut commands = CommandMap::new();
 
-    commands.insert_command(
+    commands.insert_navigation_command(
         "workspace-mode",
         "Workspace Mode",
         "Push workspace mode",
@@ -116,7 +389,7 @@ pub fn global_commands() -> CommandMap {
         },
     );
 
-    commands.insert_command(
+    commands.insert_navigation_command(
         "view-mode",
         "View Mode",
         "Push view mode",
@@ -127,7 +400,39 @@ pub fn global_commands() -> CommandMap {
         },
     );
 
-    commands.insert_command(
+    commands.insert_navigation_command(
+        "workspace-mode-replace",
+        "Switch to Workspace Mode",
+        "Replace current mode with workspace mode",
+        |state: &mut State| {
+            let workspace_id = state.current_workspace_id();
+            state.update_workspace_mode(workspace_id);
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "view-mode-replace",
+        "Switch to View Mode",
+        "Replace current mode with view mode",
+        |state: &mut State| {
+            let workspace_id = state.current_workspace_id();
+            state.update_view_workspace_mode(workspace_id);
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "log-viewer",
+        "Log Viewer",
+        "Toggle current session log viewer tool",
+        |state: &mut State| {
+            state.screen_mut().toggle_transient_tool(TransientTool::Log);
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
         "pop-mode",
         "Pop Mode",
         "Pop current mode from mode stack",
@@ -137,5 +442,307 @@ pub fn global_commands() -> CommandMap {
         },
     );
 
+    commands.insert(
+        Command::new(
+            "repeat-last-command",
+            "Repeat Last Command",
+            "Run last command again with the same count, unless another one is typed",
+            Action::Builtin(|state: &mut State| state.repeat_last_command()),
+        )
+        .with_count()
+        .without_repeat(),
+    );
+
+    commands.insert_navigation_command(
+        "pop-to-root",
+        "Pop to Root Mode",
+        "Pop all modes except the root one from mode stack",
+        |state: &mut State| {
+            state.pop_to_root_mode();
+            Ok(Task::none())
+        },
+    );
+
     commands
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::IpcHandle;
+    use crate::state::mode::{Mode, ViewMode};
+    use crate::state::screen::TransientTool;
+    use crate::testing::Harness;
+
+    #[test]
+    fn workspace_mode_pushes_mode_for_focused_workspace() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("view-mode");
+        let id = harness.focused();
+        harness.run("workspace-mode");
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id });
+        assert!(harness.mode_history().is_empty());
+    }
+
+    #[test]
+    fn view_mode_pushes_mode() {
+        let mut harness = Harness::new();
+
+        harness.run("view-mode");
+
+        assert_eq!(
+            harness.mode(),
+            &Mode::View {
+                mode: ViewMode::Workspace { id: None }
+            }
+        );
+        assert_eq!(harness.mode_history(), &[Mode::Workspace { id: None }]);
+    }
+
+    #[test]
+    fn workspace_mode_replace_keeps_stack_depth() {
+        let mut harness = Harness::new();
+
+        harness.run("view-mode").run("workspace-mode-replace");
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+        assert_eq!(harness.mode_history().len(), 1);
+    }
+
+    #[test]
+    fn view_mode_replace_keeps_stack_depth() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("view-mode-replace");
+
+        assert_eq!(
+            harness.mode(),
+            &Mode::View {
+                mode: ViewMode::Workspace {
+                    id: harness.focused()
+                }
+            }
+        );
+        assert!(harness.mode_history().is_empty());
+    }
+
+    #[test]
+    fn log_viewer_toggles_transient_tool() {
+        let mut harness = Harness::new();
+
+        harness.run("log-viewer");
+        assert_eq!(harness.app.screen().transient_tool, Some(TransientTool::Log));
+
+        harness.run("log-viewer");
+        assert_eq!(harness.app.screen().transient_tool, None);
+    }
+
+    #[test]
+    fn pop_mode_returns_to_previous_mode() {
+        let mut harness = Harness::new();
+
+        harness.run("view-mode").run("pop-mode");
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+        assert_eq!(harness.errors(), 0);
+
+        // Esc at root mode is common, so it is not reported.
+        harness.press("esc");
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn pop_to_root_returns_to_root_mode() {
+        let mut harness = Harness::new();
+
+        harness
+            .run("view-mode")
+            .run("workspace-mode-replace")
+            .run("view-mode")
+            .run("pop-to-root");
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+        assert!(harness.mode_history().is_empty());
+    }
+
+    fn with_commands(commands: &str) -> Harness {
+        Harness::with_extra_config(&format!("[commands]\n{commands}"))
+    }
+
+    #[test]
+    fn composite_runs_steps_in_order() {
+        let mut harness = with_commands(
+            r#"
+create-two = "workspace-create;workspace-create ; "
+[commands.fullscreen]
+name = "Fullscreen Workspaces"
+description = "Create workspaces and show one fullscreen"
+run = "create-two; view-mode; view-toggle-fullscreen"
+"#,
+        );
+
+        let command = harness.app.resolve_command("fullscreen").unwrap();
+        assert_eq!(command.name(), "Fullscreen Workspaces");
+        assert_eq!(
+            harness
+                .app
+                .resolve_command("create-two")
+                .unwrap()
+                .description(),
+            "workspace-create;workspace-create ; "
+        );
+
+        harness.run("fullscreen");
+
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert!(harness.tiling().fullscreen);
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn composite_stops_at_first_failure() {
+        let mut harness =
+            with_commands(r#"broken = "workspace-create; view-equalize; workspace-create""#);
+
+        harness.run("broken");
+
+        assert_eq!(harness.workspace_ids().len(), 1);
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn invalid_composites_are_reported() {
+        let harness = with_commands(
+            r#"
+unknown = "workspace-create; missing"
+empty = " ; "
+pop-mode = "workspace-create"
+"#,
+        );
+
+        assert_eq!(harness.errors(), 3);
+        assert!(harness.app.resolve_command("unknown").is_none());
+        assert!(harness.app.resolve_command("empty").is_none());
+        assert!(matches!(
+            harness.app.resolve_command("pop-mode").unwrap().action,
+            Action::Builtin(_)
+        ));
+    }
+
+    #[test]
+    fn composite_running_invalid_composite_is_reported() {
+        let harness = with_commands(
+            r#"
+outer = "workspace-create; middle"
+middle = "inner"
+inner = "missing"
+"#,
+        );
+
+        assert_eq!(harness.errors(), 3);
+        for handle in ["outer", "middle", "inner"] {
+            assert!(harness.app.resolve_command(handle).is_none());
+        }
+    }
+
+    #[test]
+    fn only_top_level_command_is_remembered_and_reported() {
+        let mut harness = with_commands(r#"create-two = "workspace-create; workspace-create""#);
+        let (ipc, mut events) = IpcHandle::subscribed();
+        harness.app.ipc = Some(ipc);
+
+        harness.run("create-two");
+
+        let executed: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|event| matches!(event, Event::CommandExecuted { .. }))
+            .collect();
+        assert_eq!(
+            executed,
+            vec![Event::CommandExecuted {
+                handle: "create-two".to_string(),
+                error: None,
+            }]
+        );
+        assert_eq!(
+            harness.app.last_command.as_ref().unwrap().command.handle(),
+            "create-two"
+        );
+    }
+
+    #[test]
+    fn recursive_composite_is_stopped() {
+        let mut harness = with_commands(
+            r#"
+ping = "workspace-create; pong"
+pong = "ping"
+"#,
+        );
+
+        harness.run("ping");
+
+        assert_eq!(harness.errors(), 1);
+        assert_eq!(harness.workspace_ids().len(), 4);
+        assert_eq!(harness.app.command_depth, 0);
+    }
+
+    #[test]
+    fn repeat_runs_last_command_with_its_count() {
+        let mut harness = Harness::new();
+        for _ in 0..6 {
+            harness.run("workspace-create");
+        }
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.press_all(&["cmd-w", "2", "n", "."]);
+        assert_eq!(harness.focused(), Some(ids[3]));
+
+        harness.press_all(&["1", "."]);
+        assert_eq!(harness.focused(), Some(ids[4]));
+    }
+
+    #[test]
+    fn navigation_is_not_repeated() {
+        let mut harness = Harness::new();
+
+        harness
+            .run("workspace-create")
+            .run("view-mode")
+            .run("pop-mode")
+            .press(".");
+
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert_eq!(
+            harness.app.last_command.as_ref().unwrap().command.handle(),
+            "workspace-create"
+        );
+    }
+
+    #[test]
+    fn toggles_are_not_repeated() {
+        let mut harness = Harness::new();
+
+        harness
+            .run("workspace-create")
+            .run("log-viewer")
+            .run("notifications-history")
+            .press(".");
+
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert_eq!(
+            harness.app.screen().transient_tool,
+            Some(TransientTool::Notifications)
+        );
+    }
+
+    #[test]
+    fn repeat_without_last_command_is_an_error() {
+        let mut harness = Harness::new();
+
+        harness.press(".");
+
+        assert_eq!(harness.errors(), 1);
+    }
+}
@@ -0,0 +1,145 @@
+use iced::keyboard::{Key, Modifiers};
+
+use crate::state::{State, keymap::resolve_keybind, mode::Mode};
+
+// Larger counts are clamped, a runaway count would freeze the application.
+pub const MAX_COUNT: usize = 999;
+
+pub trait CountActions {
+    fn count_key_pressed(&mut self, key: &Key, modifiers: Modifiers) -> bool;
+    fn take_pending_count(&mut self) -> Option<usize>;
+}
+
+impl CountActions for State {
+    // Digits accumulate a count in command modes unless they are bound themselves.
+    // Leading zero is never a count, so it can still be bound without conflict.
+    fn count_key_pressed(&mut self, key: &Key, modifiers: Modifiers) -> bool {
+        if !matches!(self.mode, Mode::Workspace { .. } | Mode::View { .. }) || !modifiers.is_empty()
+        {
+            return false;
+        }
+
+        let Key::Character(text) = key else {
+            return false;
+        };
+        let mut chars = text.chars();
+        let Some(digit) = chars
+            .next()
+            .filter(|_| chars.next().is_none())
+            .and_then(|char| char.to_digit(10))
+            .map(|digit| digit as usize)
+        else {
+            return false;
+        };
+        if (digit == 0 && self.pending_count.is_none())
+            || resolve_keybind(self, (key.clone(), modifiers)).is_some()
+        {
+            return false;
+        }
+
+        let count = self.pending_count.unwrap_or(0) * 10 + digit;
+        self.pending_count = Some(count.min(MAX_COUNT));
+        true
+    }
+
+    fn take_pending_count(&mut self) -> Option<usize> {
+        self.pending_count.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::macros::MacroActions;
+    use crate::testing::Harness;
+
+    // Navigation wraps around, so the first workspace is focused.
+    fn with_workspaces(count: usize) -> Harness {
+        let mut harness = Harness::with_workspaces(count);
+        harness.run("workspace-next");
+        harness
+    }
+
+    fn focused_index(harness: &Harness) -> Option<usize> {
+        let focused = harness.focused()?;
+        harness.workspace_ids().iter().position(|id| *id == focused)
+    }
+
+    #[test]
+    fn count_repeats_workspace_navigation() {
+        let mut harness = with_workspaces(5);
+
+        harness.press_all(&["cmd-w", "3", "n"]);
+        assert_eq!(focused_index(&harness), Some(3));
+
+        harness.press_all(&["2", "shift-n"]);
+        assert_eq!(focused_index(&harness), Some(1));
+        assert_eq!(harness.app.pending_count, None);
+    }
+
+    #[test]
+    fn count_is_interpreted_by_view_commands() {
+        let mut harness = with_workspaces(1);
+
+        harness.press_all(&["cmd-v", "2", "e", "3", "l"]);
+
+        assert_eq!(harness.tiling().max_expanded_rows, 4);
+        assert_eq!(harness.tiling().column_weight(0), 7);
+    }
+
+    #[test]
+    fn digits_accumulate_until_command() {
+        let mut harness = with_workspaces(1);
+
+        harness.press_all(&["cmd-w", "0", "1", "0", "2"]);
+        assert_eq!(harness.app.pending_count, Some(102));
+
+        harness.press_all(&["9", "9", "9"]);
+        assert_eq!(harness.app.pending_count, Some(MAX_COUNT));
+
+        harness.press("x");
+        assert_eq!(harness.app.pending_count, None);
+    }
+
+    #[test]
+    fn command_without_count_runs_once() {
+        let mut harness = with_workspaces(0);
+
+        harness.press_all(&["cmd-w", "3", "c"]);
+
+        assert_eq!(harness.workspace_ids().len(), 1);
+        assert_eq!(harness.app.pending_count, None);
+        assert_eq!(harness.app.count, None);
+    }
+
+    #[test]
+    fn count_is_not_passed_to_nested_commands() {
+        let mut harness = with_workspaces(5);
+        harness
+            .app
+            .set_macro('a', vec!["workspace-next".to_string()]);
+
+        harness.press_all(&["cmd-w", "2"]).run("macro-a");
+        assert_eq!(focused_index(&harness), Some(2));
+
+        harness.press_all(&["2", "cmd-e", "a"]);
+        assert_eq!(focused_index(&harness), Some(4));
+    }
+
+    #[test]
+    fn counted_motion_stops_at_boundary() {
+        let mut harness = with_workspaces(4);
+        let first = harness.workspace_ids()[0];
+
+        harness.press_all(&["cmd-w", "9", "m"]);
+        assert_eq!(harness.workspace_ids()[3], first);
+
+        harness.press_all(&["2", "shift-m"]);
+        assert_eq!(harness.workspace_ids()[1], first);
+
+        harness.press_all(&["cmd-v", "9", "shift-e", "9", "n"]);
+        assert_eq!(harness.tiling().max_expanded_rows, 1);
+        assert_eq!(harness.tiling().top_expanded_row_index, 1);
+        assert_eq!(harness.errors(), 0);
+    }
+}
@@ -0,0 +1,304 @@
+use iced::Task;
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use crate::ipc::IpcRequest;
+use crate::ipc::protocol::{
+    COMMAND_ERROR, Event, INVALID_PARAMS, METHOD_NOT_FOUND, Request, Response,
+};
+use crate::message::Message;
+use crate::state::State;
+use crate::state::command::CommandActions;
+use crate::state::count::MAX_COUNT;
+use crate::state::mode::ModeActions;
+
+#[derive(Error, Debug)]
+pub enum IpcError {
+    #[error("Method {method:?} not found")]
+    MethodNotFound { method: String },
+    #[error("Invalid params: {reason}")]
+    InvalidParams { reason: String },
+}
+
+impl IpcError {
+    fn code(&self) -> i64 {
+        match self {
+            IpcError::MethodNotFound { .. } => METHOD_NOT_FOUND,
+            IpcError::InvalidParams { .. } => INVALID_PARAMS,
+        }
+    }
+}
+
+pub trait IpcActions {
+    fn handle_ipc_request(&mut self, request: IpcRequest) -> Task<Message>;
+    fn emit_ipc_event(&self, event: Event);
+    fn emit_mode_changed(&self);
+}
+
+impl IpcActions for State {
+    fn handle_ipc_request(
+        &mut self,
+        IpcRequest { request, responder }: IpcRequest,
+    ) -> Task<Message> {
+        let _span = tracing::debug_span!("ipc", method = %request.method).entered();
+        let id = request.id.clone();
+
+        let result = match request.method.as_str() {
+            "run" => command_handle(&request)
+                .map_err(|err| Response::error(id.clone(), err.code(), err.to_string()))
+                .and_then(|handle| {
+                    self.run_command_with_count(&handle, command_count(&request))
+                        .map(|task| (Value::Null, task))
+                        .map_err(|err| Response::error(id.clone(), COMMAND_ERROR, err.to_string()))
+                }),
+            "open" => serde_json::from_value(request.params.clone())
+                .map_err(|err| Response::error(id.clone(), INVALID_PARAMS, err.to_string()))
+                .and_then(|args| {
+                    self.launch(args)
+                        .map(|task| (Value::Null, task))
+                        .map_err(|err| Response::error(id.clone(), COMMAND_ERROR, err.to_string()))
+                }),
+            method => query(self, method)
+                .map(|value| (value, Task::none()))
+                .map_err(|err| Response::error(id.clone(), err.code(), err.to_string())),
+        };
+
+        let (response, task) = match result {
+            Ok((value, task)) => (Response::ok(id, value), task),
+            Err(response) => (response, Task::none()),
+        };
+
+        responder.respond(response);
+
+        task
+    }
+
+    fn emit_ipc_event(&self, event: Event) {
+        if let Some(ipc) = &self.ipc {
+            ipc.emit(event);
+        }
+    }
+
+    fn emit_mode_changed(&self) {
+        self.emit_ipc_event(Event::ModeChanged {
+            mode: self.mode.label().to_string(),
+            stack: mode_stack(self),
+        });
+    }
+}
+
+fn command_handle(request: &Request) -> Result<String, IpcError> {
+    request
+        .params
+        .get("handle")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| IpcError::InvalidParams {
+            reason: "expected string `handle`".to_string(),
+        })
+}
+
+// Optional count, clamped the same way as a typed one.
+fn command_count(request: &Request) -> Option<usize> {
+    request
+        .params
+        .get("count")
+        .and_then(Value::as_u64)
+        .map(|count| usize::try_from(count).unwrap_or(MAX_COUNT).min(MAX_COUNT))
+}
+
+fn mode_stack(state: &State) -> Vec<String> {
+    state
+        .mode_history
+        .iter()
+        .chain(std::iter::once(&state.mode))
+        .map(|mode| mode.label().to_string())
+        .collect()
+}
+
+fn query(state: &State, method: &str) -> Result<Value, IpcError> {
+    let focused_id = state.current_workspace_id();
+
+    let result = match method {
+        "commands" => {
+            let mut commands: Vec<_> = state.commands.command_values().collect();
+            commands.sort_by_key(|command| command.handle());
+
+            commands
+                .into_iter()
+                .map(|command| {
+                    json!({
+                        "handle": command.handle(),
+                        "name": command.name(),
+                        "description": command.description(),
+                    })
+                })
+                .collect()
+        }
+        "workspaces" => state
+            .screens
+            .iter()
+            .enumerate()
+            .flat_map(|(index, screen)| screen.workspace_ids.iter().map(move |id| (index, id)))
+            .filter_map(|(index, id)| state.workspaces.get(id).map(|workspace| (index, workspace)))
+            .map(|(index, workspace)| {
+                json!({
+                    "id": workspace.id.to_string(),
+                    "name": workspace.name,
+                    "screen": index,
+                    "focused": Some(workspace.id) == focused_id,
+                })
+            })
+            .collect(),
+        "focused" => json!(focused_id.map(|id| id.to_string())),
+        "mode" => json!({
+            "mode": state.mode.label(),
+            "stack": mode_stack(state),
+        }),
+        "tiling" => {
+            let tiling = &state.screen().tiling;
+
+            json!({
+                "max_expanded_rows": tiling.max_expanded_rows,
+                "max_columns": tiling.max_columns,
+                "top_expanded_row_index": tiling.top_expanded_row_index,
+                "fullscreen": tiling.fullscreen,
+                "column_weights": tiling.column_weights,
+                "row_weights": tiling.row_weights,
+            })
+        }
+        method => {
+            return Err(IpcError::MethodNotFound {
+                method: method.to_string(),
+            });
+        }
+    };
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{IpcHandle, Responder};
+    use crate::testing::Harness;
+    use tokio::sync::oneshot;
+
+    fn request(harness: &mut Harness, method: &str, params: Value) -> Response {
+        let (sender, mut receiver) = oneshot::channel();
+        let _ = harness.app.update(Message::Ipc(IpcRequest {
+            request: Request::new(1, method, params),
+            responder: Responder::new(sender),
+        }));
+
+        receiver.try_recv().expect("request is answered")
+    }
+
+    fn error_code(response: &Response) -> Option<i64> {
+        response.error.as_ref().map(|error| error.code)
+    }
+
+    #[test]
+    fn run_executes_command_with_count() {
+        let mut harness = Harness::with_workspaces(1);
+
+        let response = request(&mut harness, "run", json!({ "handle": "workspace-create" }));
+        assert_eq!(response, Response::ok(Some(1.into()), Value::Null));
+        assert_eq!(harness.workspace_ids().len(), 2);
+
+        harness.press("cmd-v");
+        request(
+            &mut harness,
+            "run",
+            json!({ "handle": "view-expand-rows", "count": 3 }),
+        );
+        assert_eq!(harness.tiling().max_expanded_rows, 5);
+    }
+
+    #[test]
+    fn run_reports_failures() {
+        let mut harness = Harness::new();
+
+        let unknown = request(&mut harness, "run", json!({ "handle": "nope" }));
+        let failing = request(&mut harness, "run", json!({ "handle": "view-equalize" }));
+        let no_handle = request(&mut harness, "run", json!({ "count": 2 }));
+
+        assert_eq!(error_code(&unknown), Some(COMMAND_ERROR));
+        assert_eq!(error_code(&failing), Some(COMMAND_ERROR));
+        assert_eq!(error_code(&no_handle), Some(INVALID_PARAMS));
+    }
+
+    #[test]
+    fn unknown_method_is_not_found() {
+        let mut harness = Harness::new();
+
+        let response = request(&mut harness, "nope", Value::Null);
+
+        assert_eq!(error_code(&response), Some(METHOD_NOT_FOUND));
+        assert_eq!(response.id, Some(1.into()));
+    }
+
+    #[test]
+    fn queries_describe_state() {
+        let mut harness = Harness::with_workspaces(2);
+        let ids = harness.workspace_ids().to_vec();
+
+        let workspaces = request(&mut harness, "workspaces", Value::Null).result;
+        assert_eq!(
+            workspaces,
+            Some(json!([
+                { "id": ids[0].to_string(), "name": "workspace 1", "screen": 0, "focused": false },
+                { "id": ids[1].to_string(), "name": "workspace 2", "screen": 0, "focused": true },
+            ]))
+        );
+
+        let focused = request(&mut harness, "focused", Value::Null).result;
+        assert_eq!(focused, Some(json!(ids[1].to_string())));
+
+        harness.press("cmd-v");
+        let mode = request(&mut harness, "mode", Value::Null).result;
+        assert_eq!(
+            mode,
+            Some(json!({ "mode": "VIEW", "stack": ["WSP", "VIEW"] }))
+        );
+
+        let tiling = request(&mut harness, "tiling", Value::Null).result.unwrap();
+        assert_eq!(tiling["max_columns"], 3);
+        assert_eq!(tiling["fullscreen"], false);
+
+        let commands = request(&mut harness, "commands", Value::Null)
+            .result
+            .unwrap();
+        assert!(
+            commands
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|command| command["handle"] == "workspace-create")
+        );
+    }
+
+    #[test]
+    fn mode_changes_are_broadcast() {
+        let mut harness = Harness::new();
+        let (ipc, mut events) = IpcHandle::subscribed();
+        harness.app.ipc = Some(ipc);
+
+        request(&mut harness, "run", json!({ "handle": "view-mode" }));
+
+        let events: Vec<_> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::CommandExecuted {
+                    handle: "view-mode".to_string(),
+                    error: None,
+                },
+                Event::ModeChanged {
+                    mode: "VIEW".to_string(),
+                    stack: vec!["WSP".to_string(), "VIEW".to_string()],
+                },
+            ]
+        );
+    }
+}
@@ -1,68 +1,763 @@
+use anyhow::anyhow;
+use iced::Task;
 pub use iced::keyboard::{Key, Modifiers};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-use crate::state::{State, mode::Mode};
+use crate::config::{format_keybind, format_trigger};
+use crate::state::{State, command::CommandMap, mode::Mode, notification::NotificationActions};
 
 pub type Keymaps = HashMap<String, Keymap>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum KeymapNode {
-    Keymap(Keymap),
     Command(String),
 }
 
 // Pressing a key dispatches a message bassed on current keymap.
-//
-// To support key sequences, a keymap may contain an inner keymap.
-// In such case, activating outer keymap swaps current keymap with the inner one.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Keymap {
     pub name: String,
     pub mapping: HashMap<(Key, Modifiers), KeymapNode>,
+    // Commands bound to a key which was bound again later in the same table.
+    pub overridden: HashMap<(Trigger, (Key, Modifiers)), Vec<String>>,
+    // Keymaps searched in order for keys not bound here.
+    pub extends: Vec<String>,
+    // Keys which are not looked up in extended keymaps for their trigger.
+    pub unbound: HashSet<(Trigger, (Key, Modifiers))>,
+    // Commands run by a key other than on its press.
+    pub triggered: HashMap<(Trigger, (Key, Modifiers)), String>,
+}
+
+// When a keybind runs its command, written in front of it such as `release-alt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Trigger {
+    Press,
+    Release,
+    // Released before `HOLD_DURATION`.
+    Tap,
+    // Kept down for `HOLD_DURATION`.
+    Hold,
+}
+
+impl Trigger {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "release" => Some(Self::Release),
+            "tap" => Some(Self::Tap),
+            "hold" => Some(Self::Hold),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Press => "press",
+            Self::Release => "release",
+            Self::Tap => "tap",
+            Self::Hold => "hold",
+        }
+    }
 }
 
 impl Keymap {
+    // Every keymap but `global` extends it unless told otherwise.
     pub fn new(name: String) -> Self {
+        let extends = if name == GLOBAL_KEYMAP {
+            Vec::new()
+        } else {
+            vec![GLOBAL_KEYMAP.to_string()]
+        };
+
         Self {
             name,
+            extends,
             ..Default::default()
         }
     }
 }
 
-pub fn keymap_for_mode<'a>(state: &'a State, mode: &'a Mode) -> Option<&'a Keymap> {
-    match mode {
-        Mode::Workspace { .. } => state.keymaps.get("workspace-mode"),
-        Mode::View { .. } => state.keymaps.get("view-mode"),
-        _ => None,
+pub const GLOBAL_KEYMAP: &str = "global";
+
+enum Lookup<'a> {
+    Bound(&'a String),
+    Unbound,
+    Missing,
+}
+
+// Depth first through extended keymaps, visiting each keymap once so cycles end.
+fn lookup<'a>(
+    keymaps: &'a Keymaps,
+    name: &str,
+    trigger: Trigger,
+    keybind: &(Key, Modifiers),
+    visited: &mut HashSet<&'a str>,
+) -> Lookup<'a> {
+    let Some(keymap) = keymaps.get(name) else {
+        return Lookup::Missing;
+    };
+    if !visited.insert(keymap.name.as_str()) {
+        return Lookup::Missing;
+    }
+
+    if let Some(handle) = triggered_command(keymap, trigger, keybind) {
+        return Lookup::Bound(handle);
     }
+    if keymap.unbound.contains(&(trigger, keybind.clone())) {
+        return Lookup::Unbound;
+    }
+
+    for parent in &keymap.extends {
+        match lookup(keymaps, parent, trigger, keybind, visited) {
+            Lookup::Missing => continue,
+            found => return found,
+        }
+    }
+
+    Lookup::Missing
 }
 
-pub fn resolve_keybind(state: &State, (key, modifiers): (Key, Modifiers)) -> Option<String> {
-    if let Some(keymap) = keymap_for_mode(state, &state.mode) {
-        if let Some(KeymapNode::Command(handle)) = keymap.mapping.get(&(key.clone(), modifiers)) { return Some(handle.clone()) }
+// Command a keybind runs in the keymap, including the keymaps it extends.
+#[cfg(test)]
+pub fn resolve_in<'a>(
+    keymaps: &'a Keymaps,
+    name: &str,
+    keybind: &(Key, Modifiers),
+) -> Option<&'a String> {
+    resolve_trigger_in(keymaps, name, Trigger::Press, keybind)
+}
+
+pub fn resolve_trigger_in<'a>(
+    keymaps: &'a Keymaps,
+    name: &str,
+    trigger: Trigger,
+    keybind: &(Key, Modifiers),
+) -> Option<&'a String> {
+    match lookup(keymaps, name, trigger, keybind, &mut HashSet::new()) {
+        Lookup::Bound(handle) => Some(handle),
+        Lookup::Unbound | Lookup::Missing => None,
     }
+}
 
-    if let Some(keymap) = state.keymaps.get("global") {
-        match keymap.mapping.get(&(key.clone(), modifiers)) {
-            Some(KeymapNode::Command(handle)) => return Some(handle.clone()),
-            _ => {
-                eprintln!("Keybind not found for {:?} - {:?}", key, modifiers);
-                eprintln!("Available global keybinds:");
-                for ((key, modifiers), node) in &keymap.mapping {
-                    eprintln!("  {:?} -> {:?}", (key, modifiers), node);
-                }
-                eprintln!("Available mode keybinds:");
-                if let Some(mode_keymap) = keymap_for_mode(state, &state.mode) {
-                    for ((key, modifiers), node) in &mode_keymap.mapping {
-                        eprintln!("  {:?} -> {:?}", (key, modifiers), node);
-                    }
-                } else {
-                    eprintln!("No keymap for current mode: {:?}", state.mode);
-                }
-            }
+// Command a keybind would run if the keymap itself did not bind it.
+pub fn resolve_inherited<'a>(
+    keymaps: &'a Keymaps,
+    keymap: &'a Keymap,
+    keybind: &(Key, Modifiers),
+) -> Option<&'a String> {
+    if keymap.unbound.contains(&(Trigger::Press, keybind.clone())) {
+        return None;
+    }
+
+    let mut visited = HashSet::from([keymap.name.as_str()]);
+    for parent in &keymap.extends {
+        match lookup(keymaps, parent, Trigger::Press, keybind, &mut visited) {
+            Lookup::Bound(handle) => return Some(handle),
+            Lookup::Unbound => return None,
+            Lookup::Missing => continue,
         }
     }
 
     None
 }
+
+// Whether keys not bound in the keymap are looked up in `ancestor`, directly or not.
+pub fn inherits_from(keymaps: &Keymaps, name: &str, ancestor: &str) -> bool {
+    let mut stack = vec![name];
+    let mut visited = HashSet::new();
+
+    while let Some(name) = stack.pop() {
+        let Some(keymap) = keymaps.get(name).filter(|_| visited.insert(name)) else {
+            continue;
+        };
+        if keymap.extends.iter().any(|parent| parent == ancestor) {
+            return true;
+        }
+        stack.extend(keymap.extends.iter().map(String::as_str));
+    }
+
+    false
+}
+
+// Mode keymap is named in `mode-keymaps` config or `<mode>-mode` by default.
+pub fn keymap_name_for_mode(mode_keymaps: &HashMap<String, String>, mode: &Mode) -> String {
+    mode_keymaps
+        .get(mode.name())
+        .cloned()
+        .unwrap_or_else(|| format!("{}-mode", mode.name()))
+}
+
+pub fn keymap_for_mode<'a>(state: &'a State, mode: &Mode) -> Option<&'a Keymap> {
+    state
+        .keymaps
+        .get(&keymap_name_for_mode(&state.mode_keymaps, mode))
+}
+
+pub fn resolve_keybind(state: &State, keybind: (Key, Modifiers)) -> Option<String> {
+    resolve_trigger(state, Trigger::Press, keybind)
+}
+
+pub fn resolve_trigger(
+    state: &State,
+    trigger: Trigger,
+    keybind: (Key, Modifiers),
+) -> Option<String> {
+    // Modes without a keymap still have global keys, e.g. to leave them.
+    let name = keymap_for_mode(state, &state.mode).map_or(GLOBAL_KEYMAP, |keymap| &keymap.name);
+
+    let handle = resolve_trigger_in(&state.keymaps, name, trigger, &keybind);
+    if handle.is_none() && trigger == Trigger::Press {
+        let (key, modifiers) = &keybind;
+        tracing::debug!(?key, ?modifiers, mode = ?state.mode, keymap = %name, "Keybind not found");
+        if let Some(keymap) = state.keymaps.get(name) {
+            for ((key, modifiers), node) in &keymap.mapping {
+                tracing::trace!(keymap = %keymap.name, ?key, ?modifiers, ?node, "Available keybind");
+            }
+        }
+    }
+
+    handle.cloned()
+}
+
+// Problems found in keymaps, ordered from the most severe kind.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeymapIssue {
+    Duplicate {
+        keymap: String,
+        keybind: String,
+        handles: Vec<String>,
+    },
+    UnknownCommand {
+        keymap: String,
+        keybind: String,
+        handle: String,
+    },
+    UnknownParent {
+        keymap: String,
+        parent: String,
+    },
+    UnknownModeKeymap {
+        mode: String,
+        keymap: String,
+    },
+    Shadowed {
+        keymap: String,
+        keybind: String,
+        handle: String,
+        inherited: String,
+    },
+    Unbound {
+        handle: String,
+    },
+}
+
+impl KeymapIssue {
+    // Shadowing and unbound commands may well be intended.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Duplicate { .. }
+                | Self::UnknownCommand { .. }
+                | Self::UnknownParent { .. }
+                | Self::UnknownModeKeymap { .. }
+        )
+    }
+}
+
+impl fmt::Display for KeymapIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate {
+                keymap,
+                keybind,
+                handles,
+            } => write!(
+                f,
+                "{keymap}: {keybind} is bound to {}, only {} is used",
+                handles.join(", "),
+                handles.last().map(String::as_str).unwrap_or_default()
+            ),
+            Self::UnknownCommand {
+                keymap,
+                keybind,
+                handle,
+            } => write!(
+                f,
+                "{keymap}: {keybind} is bound to unknown command {handle}"
+            ),
+            Self::UnknownParent { keymap, parent } => {
+                write!(f, "{keymap}: extends unknown keymap {parent}")
+            }
+            Self::UnknownModeKeymap { mode, keymap } => {
+                write!(f, "mode-keymaps: {mode} uses unknown keymap {keymap}")
+            }
+            Self::Shadowed {
+                keymap,
+                keybind,
+                handle,
+                inherited,
+            } => write!(
+                f,
+                "{keymap}: {keybind} runs {handle} instead of inherited {inherited}"
+            ),
+            Self::Unbound { handle } => write!(f, "{handle} has no keybinding"),
+        }
+    }
+}
+
+pub fn bound_commands(keymap: &Keymap) -> impl Iterator<Item = (&(Key, Modifiers), &String)> {
+    keymap
+        .mapping
+        .iter()
+        .map(|(keybind, KeymapNode::Command(handle))| (keybind, handle))
+}
+
+pub fn bound_command<'a>(keymap: &'a Keymap, keybind: &(Key, Modifiers)) -> Option<&'a String> {
+    keymap
+        .mapping
+        .get(keybind)
+        .map(|KeymapNode::Command(handle)| handle)
+}
+
+pub fn triggered_command<'a>(
+    keymap: &'a Keymap,
+    trigger: Trigger,
+    keybind: &(Key, Modifiers),
+) -> Option<&'a String> {
+    match trigger {
+        Trigger::Press => bound_command(keymap, keybind),
+        trigger => keymap.triggered.get(&(trigger, keybind.clone())),
+    }
+}
+
+pub fn check_keymaps(
+    keymaps: &Keymaps,
+    mode_keymaps: &HashMap<String, String>,
+    commands: &CommandMap,
+) -> Vec<KeymapIssue> {
+    let mut issues = Vec::new();
+
+    for (mode, keymap) in mode_keymaps {
+        if !keymaps.contains_key(keymap) {
+            issues.push(KeymapIssue::UnknownModeKeymap {
+                mode: mode.clone(),
+                keymap: keymap.clone(),
+            });
+        }
+    }
+
+    for keymap in keymaps.values() {
+        for parent in &keymap.extends {
+            if !keymaps.contains_key(parent) {
+                issues.push(KeymapIssue::UnknownParent {
+                    keymap: keymap.name.clone(),
+                    parent: parent.clone(),
+                });
+            }
+        }
+
+        for ((trigger, keybind), overridden) in &keymap.overridden {
+            let handles = overridden
+                .iter()
+                .chain(triggered_command(keymap, *trigger, keybind))
+                .cloned()
+                .collect();
+
+            issues.push(KeymapIssue::Duplicate {
+                keymap: keymap.name.clone(),
+                keybind: format_trigger(*trigger, keybind),
+                handles,
+            });
+        }
+
+        for (keybind, handle) in bound_commands(keymap) {
+            if commands.get_command(handle).is_none() {
+                issues.push(KeymapIssue::UnknownCommand {
+                    keymap: keymap.name.clone(),
+                    keybind: format_keybind(keybind),
+                    handle: handle.clone(),
+                });
+            }
+
+            if let Some(inherited) = resolve_inherited(keymaps, keymap, keybind)
+                && inherited != handle
+            {
+                issues.push(KeymapIssue::Shadowed {
+                    keymap: keymap.name.clone(),
+                    keybind: format_keybind(keybind),
+                    handle: handle.clone(),
+                    inherited: inherited.clone(),
+                });
+            }
+        }
+
+        for ((trigger, keybind), handle) in &keymap.triggered {
+            if commands.get_command(handle).is_none() {
+                issues.push(KeymapIssue::UnknownCommand {
+                    keymap: keymap.name.clone(),
+                    keybind: format_trigger(*trigger, keybind),
+                    handle: handle.clone(),
+                });
+            }
+        }
+    }
+
+    let bound: HashSet<_> = keymaps
+        .values()
+        .flat_map(|keymap| {
+            bound_commands(keymap)
+                .map(|(_, handle)| handle)
+                .chain(keymap.triggered.values())
+        })
+        .collect();
+    issues.extend(
+        commands
+            .command_values()
+            .filter(|command| !bound.contains(&command.handle().to_string()))
+            .map(|command| KeymapIssue::Unbound {
+                handle: command.handle().to_string(),
+            }),
+    );
+
+    issues.sort();
+    issues
+}
+
+pub trait KeymapActions {
+    fn check_keymaps(&mut self);
+}
+
+impl KeymapActions for State {
+    // Reports all issues in one notification, as an error if any of them is one.
+    fn check_keymaps(&mut self) {
+        let issues = check_keymaps(&self.keymaps, &self.mode_keymaps, &self.commands);
+
+        if issues.is_empty() {
+            self.push_info("Keymaps have no issues");
+            return;
+        }
+
+        let report = issues
+            .iter()
+            .map(KeymapIssue::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if issues.iter().any(KeymapIssue::is_error) {
+            self.push_error(anyhow!(report));
+        } else {
+            self.push_info(report);
+        }
+    }
+}
+
+pub fn keymap_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_navigation_command(
+        "keymap-check",
+        "Check Keymaps",
+        "Report duplicate, shadowing and unknown bindings and unbound commands",
+        |state: &mut State| {
+            state.check_keymaps();
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{format_keybind, format_trigger, parse_keybind};
+    use crate::testing::Harness;
+
+    const CONFIG: &str = r#"
+[keymaps.global]
+pop-mode = "esc"
+workspace-mode = "cmd-w"
+keymap-check = "cmd-k"
+
+[keymaps.workspace-mode]
+workspace-create = "c"
+workspace-delete = "c"
+workspace-next = "shift-cmd-n"
+workspace-previous = "cmd-shift-n"
+view-mode = "esc"
+workspace-mode = "cmd-w"
+missing-command = "x"
+"#;
+
+    fn issues(harness: &Harness) -> Vec<KeymapIssue> {
+        check_keymaps(
+            &harness.app.keymaps,
+            &harness.app.mode_keymaps,
+            &harness.app.commands,
+        )
+    }
+
+    #[test]
+    fn binding_later_in_file_wins() {
+        let harness = Harness::with_config(
+            "[keymaps.workspace-mode]\nworkspace-delete = \"x\"\nworkspace-create = \"x\"\n",
+        );
+
+        let keymap = &harness.app.keymaps["workspace-mode"];
+        assert_eq!(
+            bound_command(keymap, &parse_keybind(vec!["x"])).map(String::as_str),
+            Some("workspace-create")
+        );
+        assert_eq!(
+            keymap.overridden[&(Trigger::Press, parse_keybind(vec!["x"]))],
+            vec!["workspace-delete"]
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_are_reported() {
+        let harness = Harness::with_config(CONFIG);
+        let duplicates: Vec<_> = issues(&harness)
+            .into_iter()
+            .filter(|issue| matches!(issue, KeymapIssue::Duplicate { .. }))
+            .collect();
+
+        assert_eq!(
+            duplicates,
+            vec![
+                KeymapIssue::Duplicate {
+                    keymap: "workspace-mode".to_string(),
+                    keybind: "c".to_string(),
+                    handles: vec![
+                        "workspace-create".to_string(),
+                        "workspace-delete".to_string()
+                    ],
+                },
+                KeymapIssue::Duplicate {
+                    keymap: "workspace-mode".to_string(),
+                    keybind: "cmd-shift-n".to_string(),
+                    handles: vec![
+                        "workspace-next".to_string(),
+                        "workspace-previous".to_string()
+                    ],
+                },
+            ]
+        );
+        assert!(duplicates.iter().all(KeymapIssue::is_error));
+    }
+
+    #[test]
+    fn shadowing_and_unknown_commands_are_reported() {
+        let harness = Harness::with_config(CONFIG);
+        let issues = issues(&harness);
+
+        assert!(issues.contains(&KeymapIssue::Shadowed {
+            keymap: "workspace-mode".to_string(),
+            keybind: "esc".to_string(),
+            handle: "view-mode".to_string(),
+            inherited: "pop-mode".to_string(),
+        }));
+        assert!(issues.contains(&KeymapIssue::UnknownCommand {
+            keymap: "workspace-mode".to_string(),
+            keybind: "x".to_string(),
+            handle: "missing-command".to_string(),
+        }));
+        // Same command bound in both keymaps is not shadowing.
+        assert!(!issues.iter().any(|issue| matches!(
+            issue,
+            KeymapIssue::Shadowed { handle, .. } if handle == "workspace-mode"
+        )));
+    }
+
+    #[test]
+    fn unbound_commands_are_reported() {
+        let harness = Harness::with_config(CONFIG);
+        let unbound: Vec<_> = issues(&harness)
+            .into_iter()
+            .filter_map(|issue| match issue {
+                KeymapIssue::Unbound { handle } => Some(handle),
+                _ => None,
+            })
+            .collect();
+
+        assert!(unbound.contains(&"view-equalize".to_string()));
+        assert!(!unbound.contains(&"keymap-check".to_string()));
+        assert!(!unbound.contains(&"pop-mode".to_string()));
+    }
+
+    #[test]
+    fn check_command_reports_errors() {
+        let mut harness = Harness::with_config(CONFIG);
+
+        harness.press("cmd-k");
+
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn test_config_keymaps_have_no_errors() {
+        let harness = Harness::new();
+
+        let errors: Vec<_> = issues(&harness)
+            .into_iter()
+            .filter(KeymapIssue::is_error)
+            .collect();
+        assert_eq!(errors, vec![]);
+    }
+
+    const INHERITANCE_CONFIG: &str = r#"
+[keymaps.global]
+pop-mode = "esc"
+workspace-mode = "cmd-w"
+view-mode = "cmd-v"
+
+[keymaps.creating]
+workspace-create = "c"
+workspace-next = "n"
+
+[keymaps.workspace-mode]
+extends = ["creating", "global"]
+unbind = ["n", "cmd-v"]
+
+[keymaps.standalone]
+extends = []
+workspace-create = "c"
+
+[keymaps.looping]
+extends = ["looping", "missing"]
+
+[mode-keymaps]
+view = "standalone"
+picker = "unknown"
+"#;
+
+    #[test]
+    fn keymap_falls_back_to_extended_keymaps() {
+        let mut harness = Harness::with_config(INHERITANCE_CONFIG);
+
+        harness.press_all(&["c", "c", "n", "cmd-v"]);
+
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert_eq!(harness.focused(), Some(harness.workspace_ids()[1]));
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn mode_keymap_is_taken_from_mapping_table() {
+        let mut harness = Harness::with_config(INHERITANCE_CONFIG);
+
+        harness.run("view-mode").press_all(&["c", "esc"]);
+
+        // Standalone keymap extends nothing, so `esc` does not leave view mode.
+        assert_eq!(harness.workspace_ids().len(), 1);
+        assert!(matches!(harness.mode(), Mode::View { .. }));
+    }
+
+    #[test]
+    fn mode_without_keymap_uses_global() {
+        let harness = Harness::with_config(INHERITANCE_CONFIG);
+        let keymaps = &harness.app.keymaps;
+        let esc = parse_keybind(vec!["esc"]);
+
+        assert_eq!(
+            keymap_name_for_mode(&harness.app.mode_keymaps, &Mode::Picker),
+            "unknown"
+        );
+        assert_eq!(
+            keymap_name_for_mode(&harness.app.mode_keymaps, &Mode::Overview),
+            "overview-mode"
+        );
+        assert_eq!(
+            resolve_in(keymaps, "looping", &esc),
+            None,
+            "cycle ends without reaching global"
+        );
+        assert_eq!(
+            resolve_in(keymaps, GLOBAL_KEYMAP, &esc),
+            Some(&"pop-mode".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_keymaps_are_reported() {
+        let harness = Harness::with_config(INHERITANCE_CONFIG);
+        let issues = issues(&harness);
+
+        assert!(issues.contains(&KeymapIssue::UnknownParent {
+            keymap: "looping".to_string(),
+            parent: "missing".to_string(),
+        }));
+        assert!(issues.contains(&KeymapIssue::UnknownModeKeymap {
+            mode: "picker".to_string(),
+            keymap: "unknown".to_string(),
+        }));
+        assert!(
+            !issues
+                .iter()
+                .any(|issue| matches!(issue, KeymapIssue::Shadowed { .. }))
+        );
+    }
+
+    #[test]
+    fn triggered_keybinds_are_kept_apart_from_presses() {
+        let harness = Harness::new();
+        let picker = &harness.app.keymaps["picker-mode"];
+        let alt = parse_keybind(vec!["alt"]);
+
+        assert_eq!(bound_command(picker, &alt), None);
+        assert_eq!(
+            triggered_command(picker, Trigger::Release, &alt).map(String::as_str),
+            Some("workspace-switch-confirm")
+        );
+        assert_eq!(format_trigger(Trigger::Release, &alt), "release-alt");
+        assert_eq!(
+            crate::config::parse_trigger(vec!["hold", "cmd", "space"]),
+            (Trigger::Hold, parse_keybind(vec!["cmd", "space"]))
+        );
+    }
+
+    #[test]
+    fn triggered_keybinds_are_checked_and_unbound_by_trigger() {
+        let harness = Harness::with_config(
+            r#"
+[keymaps.base]
+workspace-create = "release-x"
+workspace-delete = "x"
+
+[keymaps.child]
+extends = ["base"]
+unbind = ["release-x"]
+workspace-next = "release-y"
+workspace-previous = "release-y"
+"#,
+        );
+        let keymaps = &harness.app.keymaps;
+        let x = parse_keybind(vec!["x"]);
+
+        assert_eq!(
+            resolve_trigger_in(keymaps, "child", Trigger::Release, &x),
+            None
+        );
+        assert_eq!(
+            resolve_in(keymaps, "child", &x).map(String::as_str),
+            Some("workspace-delete")
+        );
+        assert!(issues(&harness).contains(&KeymapIssue::Duplicate {
+            keymap: "child".to_string(),
+            keybind: "release-y".to_string(),
+            handles: vec![
+                "workspace-next".to_string(),
+                "workspace-previous".to_string()
+            ],
+        }));
+    }
+
+    #[test]
+    fn keybind_is_formatted_as_parsed() {
+        for keybind in ["cmd-shift-n", "esc", "cmd-minus", "alt-space", "down", "."] {
+            let parsed = parse_keybind(keybind.split('-').collect());
+            assert_eq!(format_keybind(&parsed), keybind);
+        }
+    }
+}
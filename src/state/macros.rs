@@ -0,0 +1,488 @@
+use anyhow::Result;
+use iced::Task;
+use iced::keyboard::{Key, Modifiers};
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::config::{edit_config, edit_table};
+use crate::message::Message;
+use crate::state::{
+    State,
+    command::{Action, Command, CommandActions, CommandMap},
+    count::MAX_COUNT,
+    mode::{Mode, ModeActions},
+    notification::NotificationActions,
+};
+
+// Commands driving macros are never recorded.
+const MACRO_COMMANDS: [&str; 4] = [
+    "macro-record-start",
+    "macro-record-stop",
+    "macro-replay",
+    "macro-save",
+];
+
+#[derive(Error, Debug)]
+pub enum MacroError {
+    #[error("Already recording macro into register {register:?}")]
+    AlreadyRecording { register: char },
+    #[error("No macro is being recorded")]
+    NotRecording,
+    #[error("Register {register:?} is empty")]
+    EmptyRegister { register: char },
+    #[error("Configuration was not loaded from a file")]
+    NoConfigFile,
+}
+
+// What happens once a register is chosen in register mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterAction {
+    Record,
+    Replay,
+    Save,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub register: char,
+    pub handles: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Macros {
+    pub registers: HashMap<char, Vec<String>>,
+    pub recording: Option<Recording>,
+}
+
+pub fn is_register(register: char) -> bool {
+    register.is_ascii_alphabetic()
+}
+
+fn macro_handle(register: char) -> String {
+    format!("macro-{register}")
+}
+
+// Register of a macro saved as a composite command.
+pub fn saved_register(handle: &str) -> Option<char> {
+    let mut chars = handle.strip_prefix("macro-")?.chars();
+    chars
+        .next()
+        .filter(|register| is_register(*register) && chars.next().is_none())
+}
+
+fn single_char(key: &Key) -> Option<char> {
+    let Key::Character(text) = key else {
+        return None;
+    };
+    let mut chars = text.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+pub trait MacroActions {
+    fn load_macros(&mut self, macros: HashMap<char, Vec<String>>);
+    fn set_macro(&mut self, register: char, handles: Vec<String>);
+    fn start_macro_recording(&mut self) -> Result<()>;
+    fn stop_macro_recording(&mut self) -> Result<char>;
+    fn record_command(&mut self, handle: &str);
+    fn replay_macro(&mut self, register: char, count: usize) -> Result<Task<Message>>;
+    fn save_macro(&mut self, register: char) -> Result<()>;
+    fn register_key_pressed(
+        &mut self,
+        key: &Key,
+        modifiers: Modifiers,
+    ) -> Option<Result<Task<Message>>>;
+}
+
+impl MacroActions for State {
+    fn load_macros(&mut self, macros: HashMap<char, Vec<String>>) {
+        for (register, handles) in macros {
+            self.set_macro(register, handles);
+        }
+    }
+
+    // Every register is also available as a `macro-<register>` command.
+    fn set_macro(&mut self, register: char, handles: Vec<String>) {
+        self.insert_command(
+            Command::new(
+                macro_handle(register),
+                format!("Macro {register}"),
+                format!("Replay {}", handles.join("; ")),
+                Action::Macro(register),
+            )
+            .with_count(),
+        );
+        self.macros.registers.insert(register, handles);
+    }
+
+    fn start_macro_recording(&mut self) -> Result<()> {
+        if let Some(recording) = &self.macros.recording {
+            return Err(MacroError::AlreadyRecording {
+                register: recording.register,
+            }
+            .into());
+        }
+
+        self.push_mode(Mode::Register {
+            action: RegisterAction::Record,
+            count: None,
+        });
+        Ok(())
+    }
+
+    fn stop_macro_recording(&mut self) -> Result<char> {
+        let recording = self
+            .macros
+            .recording
+            .take()
+            .ok_or(MacroError::NotRecording)?;
+
+        tracing::debug!(register = %recording.register, handles = ?recording.handles, "Recorded macro");
+        self.set_macro(recording.register, recording.handles);
+
+        Ok(recording.register)
+    }
+
+    fn record_command(&mut self, handle: &str) {
+        if let Some(recording) = &mut self.macros.recording
+            && !MACRO_COMMANDS.contains(&handle)
+        {
+            recording.handles.push(handle.to_string());
+        }
+    }
+
+    // Replay stops at the first failing command.
+    fn replay_macro(&mut self, register: char, count: usize) -> Result<Task<Message>> {
+        let handles = self
+            .macros
+            .registers
+            .get(&register)
+            .filter(|handles| !handles.is_empty())
+            .cloned()
+            .ok_or(MacroError::EmptyRegister { register })?;
+
+        let handles: Vec<_> = (0..count).flat_map(|_| handles.iter().cloned()).collect();
+        self.run_commands(&handles)
+    }
+
+    // Written as a `macro-<register>` composite command.
+    fn save_macro(&mut self, register: char) -> Result<()> {
+        let handles = self
+            .macros
+            .registers
+            .get(&register)
+            .ok_or(MacroError::EmptyRegister { register })?;
+        let path = self.config_path.as_ref().ok_or(MacroError::NoConfigFile)?;
+
+        edit_config(path, |document| {
+            let commands = edit_table(document.as_table_mut(), "commands")?;
+            commands.insert(
+                &macro_handle(register),
+                toml_edit::value(handles.join("; ")),
+            );
+            Ok(())
+        })?;
+
+        self.push_info(format!("Saved macro {register}"));
+        Ok(())
+    }
+
+    // Keys other than a register or a count digit are left to keymaps, so `esc` still cancels.
+    fn register_key_pressed(
+        &mut self,
+        key: &Key,
+        modifiers: Modifiers,
+    ) -> Option<Result<Task<Message>>> {
+        let Mode::Register { action, count } = self.mode else {
+            return None;
+        };
+        if modifiers.command() || modifiers.control() || modifiers.alt() {
+            return None;
+        }
+        let char = single_char(key)?;
+
+        if let Some(digit) = char.to_digit(10)
+            && action == RegisterAction::Replay
+        {
+            let count = count.unwrap_or(0) * 10 + digit as usize;
+            self.replace_mode(Mode::Register {
+                action,
+                count: Some(count.min(MAX_COUNT)),
+            });
+            return Some(Ok(Task::none()));
+        }
+
+        if !is_register(char) {
+            return None;
+        }
+
+        let count = count.unwrap_or(1);
+        self.pop_mode();
+
+        Some(match action {
+            RegisterAction::Record => {
+                self.macros.recording = Some(Recording {
+                    register: char,
+                    handles: Vec::new(),
+                });
+                Ok(Task::none())
+            }
+            // Replayed through its command, so it can be repeated with its count.
+            RegisterAction::Replay if self.macros.registers.contains_key(&char) => {
+                let handle = macro_handle(char);
+                let result = self.run_command_with_count(&handle, Some(count));
+                if result.is_ok() {
+                    for _ in 0..count {
+                        self.record_command(&handle);
+                    }
+                }
+                result
+            }
+            RegisterAction::Replay => Err(MacroError::EmptyRegister { register: char }.into()),
+            RegisterAction::Save => self.save_macro(char).map(|_| Task::none()),
+        })
+    }
+}
+
+pub fn macro_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_navigation_command(
+        "macro-record-start",
+        "Record Macro",
+        "Start recording commands into a register",
+        |state: &mut State| {
+            state.start_macro_recording()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "macro-record-stop",
+        "Stop Recording Macro",
+        "Stop recording and store commands in the register",
+        |state: &mut State| {
+            state.stop_macro_recording()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert(
+        Command::new(
+            "macro-replay",
+            "Replay Macro",
+            "Replay commands from a register, count may also be typed before the register",
+            Action::Builtin(|state: &mut State| {
+                state.push_mode(Mode::Register {
+                    action: RegisterAction::Replay,
+                    count: state.count,
+                });
+                Ok(Task::none())
+            }),
+        )
+        .with_count()
+        .without_repeat(),
+    );
+
+    commands.insert_navigation_command(
+        "macro-save",
+        "Save Macro",
+        "Write macro from a register to configuration file",
+        |state: &mut State| {
+            state.push_mode(Mode::Register {
+                action: RegisterAction::Save,
+                count: None,
+            });
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::testing::Harness;
+
+    fn record(harness: &mut Harness, register: &str, handles: &[&str]) {
+        harness.press_all(&["cmd-r", register]);
+        for handle in handles {
+            harness.run(handle);
+        }
+        harness.press("cmd-shift-r");
+    }
+
+    #[test]
+    fn recorded_macro_replays_with_count() {
+        let mut harness = Harness::new();
+
+        record(&mut harness, "a", &["workspace-create", "workspace-create"]);
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert_eq!(
+            harness.app.macros.registers[&'a'],
+            vec!["workspace-create", "workspace-create"]
+        );
+
+        harness.press_all(&["cmd-e", "1", "2", "a"]);
+
+        assert_eq!(harness.workspace_ids().len(), 26);
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+    }
+
+    #[test]
+    fn recording_skips_macro_commands_and_failures() {
+        let mut harness = Harness::new();
+        harness
+            .app
+            .set_macro('b', vec!["workspace-create".to_string()]);
+
+        harness.press_all(&["cmd-r", "a", "m", "cmd-e", "b", "cmd-shift-r"]);
+
+        assert_eq!(harness.app.macros.registers[&'a'], vec!["macro-b"]);
+        assert_eq!(harness.errors(), 1);
+        assert!(harness.app.macros.recording.is_none());
+    }
+
+    #[test]
+    fn replay_stops_at_first_failure() {
+        let mut harness = Harness::new();
+        harness.app.set_macro(
+            'a',
+            ["workspace-create", "view-equalize", "workspace-create"]
+                .map(str::to_string)
+                .to_vec(),
+        );
+
+        harness.run("macro-a");
+
+        assert_eq!(harness.workspace_ids().len(), 1);
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn config_macros_become_commands() {
+        let mut harness = Harness::with_extra_config(
+            "[macros]\nw = [\"workspace-create\", \"view-mode\"]\nr = [\"macro-r\"]\n",
+        );
+
+        harness.run("macro-w");
+        assert_eq!(harness.workspace_ids().len(), 1);
+        assert!(matches!(harness.mode(), Mode::View { .. }));
+
+        harness.run("macro-r");
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn register_mode_is_cancelled_with_escape() {
+        let mut harness = Harness::new();
+
+        harness
+            .app
+            .set_macro('q', vec!["workspace-create".to_string()]);
+
+        harness.press_all(&["cmd-e", "3", "esc"]);
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+
+        harness.press_all(&["cmd-e", "q"]);
+        assert_eq!(harness.workspace_ids().len(), 1);
+    }
+
+    #[test]
+    fn recording_twice_is_an_error() {
+        let mut harness = Harness::new();
+
+        harness
+            .press_all(&["cmd-r", "a", "cmd-r"])
+            .run("macro-record-stop");
+        harness.run("macro-record-stop");
+
+        assert_eq!(harness.errors(), 2);
+    }
+
+    #[test]
+    fn saved_macro_is_written_to_config() {
+        let (mut harness, config) =
+            Harness::with_config_file("# keep me\n[commands]\nx = \"view-mode\" # and me\n");
+
+        record(&mut harness, "a", &["workspace-create", "view-mode"]);
+        harness.press_all(&["cmd-s", "a"]);
+
+        let content = config.read();
+        assert!(content.contains("# keep me") && content.contains("# and me"));
+        let commands = Config::parse(&content).commands;
+        let saved = commands
+            .iter()
+            .find(|composite| composite.handle == "macro-a")
+            .unwrap();
+        assert_eq!(saved.steps, vec!["workspace-create", "view-mode"]);
+        assert!(commands.iter().any(|composite| composite.handle == "x"));
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn saving_into_malformed_commands_is_an_error() {
+        let (mut harness, config) = Harness::with_config_file("commands = 1\n");
+
+        record(&mut harness, "a", &["workspace-create"]);
+        harness.press_all(&["cmd-s", "a"]);
+
+        assert_eq!(config.read(), "commands = 1\n");
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn saved_macro_is_loaded_into_its_register() {
+        let mut harness =
+            Harness::with_extra_config("[commands]\nmacro-a = \"workspace-create\"\n");
+
+        harness.press_all(&["cmd-e", "2", "a"]);
+
+        assert_eq!(harness.app.macros.registers[&'a'], vec!["workspace-create"]);
+        assert_eq!(harness.workspace_ids().len(), 2);
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn invalid_saved_macros_are_reported() {
+        let harness = Harness::with_extra_config(
+            "[commands]\nmacro-a = \"nope\"\nmacro-b = \" ; \"\nmacro-c = \"macro-a\"\n",
+        );
+
+        assert!(harness.app.macros.registers.is_empty());
+        assert_eq!(harness.errors(), 3);
+    }
+
+    #[test]
+    fn saved_macro_does_not_replace_configured_register() {
+        let harness = Harness::with_extra_config(
+            "[macros]\na = [\"view-mode\"]\n\n[commands]\nmacro-a = \"workspace-create\"\n",
+        );
+
+        assert_eq!(harness.app.macros.registers[&'a'], vec!["view-mode"]);
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn saving_requires_config_file() {
+        let mut harness = Harness::new();
+
+        record(&mut harness, "a", &["workspace-create"]);
+        harness.press_all(&["cmd-s", "a"]);
+
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn replayed_macro_is_repeated_with_count() {
+        let mut harness = Harness::new();
+        harness
+            .app
+            .set_macro('a', vec!["workspace-create".to_string()]);
+
+        harness.press_all(&["2", "cmd-e", "a", "."]);
+
+        assert_eq!(harness.workspace_ids().len(), 4);
+    }
+}
@@ -1,21 +1,51 @@
+pub mod activity;
+pub mod binding_editor;
 pub mod command;
+pub mod count;
+pub mod ipc;
 pub mod keymap;
+pub mod macros;
 pub mod mode;
+pub mod notification;
+pub mod overview;
+pub mod picker;
+pub mod pointer;
 pub mod screen;
+pub mod script;
+pub mod status_bar;
+pub mod template;
+pub mod theme;
 pub mod tiling;
+pub mod trigger;
+pub mod ui;
 pub mod view;
 pub mod workspace;
 
-use anyhow::Error;
-
+use self::activity::Activity;
+use self::binding_editor::BindingEditor;
+use self::macros::Macros;
 use self::mode::Mode;
+use self::notification::Notifications;
+use self::picker::Picker;
+use self::pointer::Pointer;
 use self::screen::Screen;
+use self::script::Scripting;
+use self::status_bar::StatusBar;
+use self::template::{StartupConfig, TilingTemplate, WorkspaceTemplate};
+use self::theme::Themes;
+use self::trigger::HeldKey;
+use self::ui::Ui;
 use self::workspace::Workspace;
 
-use crate::state::command::{Command, CommandActions, CommandMap};
+use crate::config::Config;
+use crate::ipc::IpcHandle;
+use crate::state::command::{CommandActions, CommandMap, LastCommand};
 use crate::state::keymap::Keymaps;
 
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const CONFIG_PATH: &str = "./config.toml";
 
 pub type Uid = uuid::Uuid;
 
@@ -24,39 +54,95 @@ pub fn create_uid() -> Uid {
 }
 
 pub struct State {
-    pub screen: self::screen::Screen,
+    // Each screen is shown in its own window, there is always at least one.
+    pub screens: Vec<Screen>,
+    pub focused_screen: usize,
     pub workspaces: HashMap<Uid, Workspace>,
+    pub activities: HashMap<Uid, Activity>,
+    pub templates: Vec<WorkspaceTemplate>,
+    pub startup: StartupConfig,
+    // Layout of new screens, saved by `view-save-layout`.
+    pub layout: TilingTemplate,
+    pub picker: Option<Picker>,
+    pub binding_editor: Option<BindingEditor>,
     pub mode: Mode,
     pub mode_history: Vec<Mode>,
     pub keymaps: Keymaps,
+    pub mode_keymaps: HashMap<String, String>,
     pub commands: CommandMap,
-    pub last_command: Option<Command>,
-    pub errors: Vec<Error>,
+    pub last_command: Option<LastCommand>,
+    // Number of commands currently running inside each other.
+    pub command_depth: usize,
+    pub macros: Macros,
+    pub scripting: Scripting,
+    pub notifications: Notifications,
+    pub status_bar: StatusBar,
+    pub held_key: Option<HeldKey>,
+    // Count typed so far, given to the next command.
+    pub pending_count: Option<usize>,
+    // Count given to the running command.
+    pub count: Option<usize>,
+    pub themes: Themes,
+    pub ui: Ui,
+    pub pointer: Pointer,
+    pub ipc: Option<IpcHandle>,
+    // File configuration was loaded from, none when parsed from memory.
+    pub config_path: Option<PathBuf>,
 }
 
 impl State {
-    pub fn push_error(&mut self, error: Error) {
-        eprintln!("Error: {:?}", error);
-        self.errors.push(error)
-    }
-}
-
-impl Default for State {
-    fn default() -> Self
-    where
-        Self: CommandActions,
-    {
-        let config = crate::config::Config::from_toml("./config.toml");
-
+    pub fn from_config(config: Config) -> Self {
         Self {
-            screen: Screen::default(),
+            screens: vec![Screen {
+                tiling: config.tiling.tiling(),
+                ..Screen::default()
+            }],
+            focused_screen: 0,
             workspaces: HashMap::new(),
+            activities: HashMap::new(),
+            templates: config.templates,
+            startup: config.startup,
+            layout: config.tiling,
+            picker: None,
+            binding_editor: None,
             mode: Mode::Workspace { id: None },
             mode_history: Vec::new(),
             commands: CommandMap::new(),
             keymaps: config.keymaps,
+            mode_keymaps: config.mode_keymaps,
             last_command: None,
-            errors: Vec::new(),
+            command_depth: 0,
+            macros: Macros::default(),
+            scripting: Scripting::default(),
+            notifications: Notifications::default(),
+            status_bar: StatusBar::new(config.status_bar),
+            held_key: None,
+            pending_count: None,
+            count: None,
+            themes: Themes::from_config(config.theme),
+            ui: Ui::new(config.ui),
+            pointer: Pointer::default(),
+            ipc: None,
+            config_path: config.path,
         }
     }
 }
+
+impl State {
+    pub fn screen(&self) -> &Screen {
+        &self.screens[self.focused_screen]
+    }
+
+    pub fn screen_mut(&mut self) -> &mut Screen {
+        &mut self.screens[self.focused_screen]
+    }
+}
+
+impl Default for State {
+    fn default() -> Self
+    where
+        Self: CommandActions,
+    {
+        Self::from_config(Config::from_toml(CONFIG_PATH))
+    }
+}
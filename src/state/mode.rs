@@ -1,4 +1,7 @@
-use crate::state::{State, Uid};
+use crate::state::{State, Uid, macros::RegisterAction};
+
+// Maximum number of modes in the stack, including current mode.
+pub const MAX_MODE_DEPTH: usize = 8;
 
 #[derive(Debug, Clone, Default, Copy, PartialEq, Eq, Hash)]
 pub enum ViewMode {
@@ -9,15 +12,6 @@ pub enum ViewMode {
     },
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
-pub enum TransientStatus {
-    #[default]
-    None,
-    Top,
-    Center,
-    Bottom,
-}
-
 #[derive(Debug, Clone, Default, PartialEq)]
 pub enum Mode {
     #[default]
@@ -28,39 +22,103 @@ pub enum Mode {
     Workspace {
         id: Option<Uid>,
     },
-    Activity {
-        id: Option<Uid>,
-    },
-    Pane {
-        id: Option<Uid>,
-    },
-    Tool {
-        id: Option<Uid>,
-        transient: TransientStatus,
+    Picker,
+    Binding,
+    Overview,
+    // Waits for a keybind, see `BindingEditorActions::capture_key_pressed`.
+    KeyCapture,
+    // Waits for a register key, see `MacroActions::register_key_pressed`.
+    Register {
+        action: RegisterAction,
+        // Repeat count typed before the register.
+        count: Option<usize>,
     },
 }
 
+impl Mode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mode::None => "-",
+            Mode::View { .. } => "VIEW",
+            Mode::Workspace { .. } => "WSP",
+            Mode::Picker => "PICK",
+            Mode::Binding => "BIND",
+            Mode::Overview => "OVW",
+            Mode::KeyCapture => "KEY",
+            Mode::Register { .. } => "REG",
+        }
+    }
+
+    // Used to pick the mode keymap, see `keymap_for_mode`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::None => "none",
+            Mode::View { .. } => "view",
+            Mode::Workspace { .. } => "workspace",
+            Mode::Picker => "picker",
+            Mode::Binding => "binding",
+            Mode::Overview => "overview",
+            Mode::KeyCapture => "key-capture",
+            Mode::Register { .. } => "register",
+        }
+    }
+}
+
 pub trait ModeActions {
+    fn push_mode(&mut self, mode: Mode);
+    fn replace_mode(&mut self, mode: Mode);
     fn push_workspace_mode(&mut self, id: Option<Uid>);
     fn update_workspace_mode(&mut self, id: Option<Uid>);
-    fn current_workspace_id(&mut self) -> Option<Uid>;
+    fn current_workspace_id(&self) -> Option<Uid>;
     fn push_view_workspace_mode(&mut self, id: Option<Uid>);
     fn update_view_workspace_mode(&mut self, id: Option<Uid>);
     fn current_view_mode(&mut self) -> Option<ViewMode>;
     fn pop_mode(&mut self);
+    fn pop_to_root_mode(&mut self);
 }
 
 impl ModeActions for State {
+    // Pushing keeps the stack free of repetitions:
+    // - a mode of the same kind as current one replaces it,
+    // - a mode already present in the stack unwinds the stack back to it,
+    // - the oldest mode above root is dropped once the stack exceeds MAX_MODE_DEPTH.
+    fn push_mode(&mut self, mode: Mode) {
+        tracing::debug!(from = ?self.mode, to = ?mode, "Push mode");
+
+        if std::mem::discriminant(&mode) == std::mem::discriminant(&self.mode) {
+            self.mode = mode;
+            return;
+        }
+
+        if let Some(index) = self.mode_history.iter().position(|m| *m == mode) {
+            self.mode_history.truncate(index);
+            self.mode = mode;
+            return;
+        }
+
+        self.mode_history
+            .push(std::mem::replace(&mut self.mode, mode));
+
+        if self.mode_history.len() >= MAX_MODE_DEPTH {
+            let overflow = self.mode_history.len() + 1 - MAX_MODE_DEPTH;
+            self.mode_history.drain(1..1 + overflow);
+        }
+    }
+
+    fn replace_mode(&mut self, mode: Mode) {
+        tracing::debug!(from = ?self.mode, to = ?mode, "Replace mode");
+        self.mode = mode;
+    }
+
     fn push_workspace_mode(&mut self, workspace_id: Option<Uid>) {
-        self.mode_history.push(self.mode.clone());
-        self.update_workspace_mode(workspace_id);
+        self.push_mode(Mode::Workspace { id: workspace_id });
     }
 
     fn update_workspace_mode(&mut self, workspace_id: Option<Uid>) {
-        self.mode = Mode::Workspace { id: workspace_id };
+        self.replace_mode(Mode::Workspace { id: workspace_id });
     }
 
-    fn current_workspace_id(&mut self) -> Option<Uid> {
+    fn current_workspace_id(&self) -> Option<Uid> {
         match self.mode {
             Mode::Workspace { id } => id,
             Mode::View {
@@ -71,14 +129,15 @@ impl ModeActions for State {
     }
 
     fn push_view_workspace_mode(&mut self, workspace_id: Option<Uid>) {
-        self.mode_history.push(self.mode.clone());
-        self.update_view_workspace_mode(workspace_id);
+        self.push_mode(Mode::View {
+            mode: ViewMode::Workspace { id: workspace_id },
+        });
     }
 
     fn update_view_workspace_mode(&mut self, workspace_id: Option<Uid>) {
-        self.mode = Mode::View {
+        self.replace_mode(Mode::View {
             mode: ViewMode::Workspace { id: workspace_id },
-        };
+        });
     }
 
     fn current_view_mode(&mut self) -> Option<ViewMode> {
@@ -89,11 +148,136 @@ impl ModeActions for State {
         }
     }
 
+    // Root mode is never popped, so there is always a mode with a keymap to return to.
+    // Popping at root does nothing, as esc is pressed there all the time.
     fn pop_mode(&mut self) {
-        if let Some(previous_mode) = self.mode_history.pop() {
-            self.mode = previous_mode;
-        } else {
-            self.mode = Mode::None;
+        let Some(previous_mode) = self.mode_history.pop() else {
+            return;
+        };
+        self.mode = previous_mode;
+
+        tracing::debug!(to = ?self.mode, depth = self.mode_history.len(), "Pop mode");
+    }
+
+    fn pop_to_root_mode(&mut self) {
+        self.mode_history.truncate(1);
+        self.pop_mode();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::create_uid;
+    use crate::testing::test_state;
+
+    fn view_mode(id: Option<Uid>) -> Mode {
+        Mode::View {
+            mode: ViewMode::Workspace { id },
+        }
+    }
+
+    fn state_in(mode: Mode) -> State {
+        State {
+            mode,
+            mode_history: Vec::new(),
+            ..test_state()
+        }
+    }
+
+    #[test]
+    fn push_stacks_modes_of_different_kind() {
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.push_view_workspace_mode(None);
+
+        assert_eq!(state.mode, view_mode(None));
+        assert_eq!(state.mode_history, vec![Mode::Workspace { id: None }]);
+    }
+
+    #[test]
+    fn push_replaces_mode_of_same_kind() {
+        let id = create_uid();
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.push_workspace_mode(Some(id));
+        state.push_workspace_mode(Some(id));
+
+        assert_eq!(state.mode, Mode::Workspace { id: Some(id) });
+        assert!(state.mode_history.is_empty());
+    }
+
+    #[test]
+    fn push_unwinds_to_mode_already_in_stack() {
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.push_view_workspace_mode(None);
+        state.push_mode(Mode::Picker);
+        state.push_workspace_mode(None);
+
+        assert_eq!(state.mode, Mode::Workspace { id: None });
+        assert!(state.mode_history.is_empty());
+    }
+
+    #[test]
+    fn push_drops_oldest_modes_beyond_max_depth() {
+        let mut state = state_in(Mode::None);
+        let first = Some(create_uid());
+
+        state.push_workspace_mode(first);
+        for _ in 0..MAX_MODE_DEPTH {
+            state.push_view_workspace_mode(Some(create_uid()));
+            state.push_workspace_mode(Some(create_uid()));
         }
+
+        assert_eq!(state.mode_history.len() + 1, MAX_MODE_DEPTH);
+        assert!(matches!(state.mode, Mode::Workspace { .. }));
+        // Root survives, so popping all the way still ends there.
+        assert_eq!(state.mode_history[0], Mode::None);
+        assert!(!state.mode_history.contains(&Mode::Workspace { id: first }));
+    }
+
+    #[test]
+    fn replace_does_not_grow_stack() {
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.update_view_workspace_mode(None);
+
+        assert_eq!(state.mode, view_mode(None));
+        assert!(state.mode_history.is_empty());
+    }
+
+    #[test]
+    fn pop_restores_previous_mode() {
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.push_view_workspace_mode(None);
+        state.pop_mode();
+
+        assert_eq!(state.mode, Mode::Workspace { id: None });
+        assert!(state.mode_history.is_empty());
+    }
+
+    #[test]
+    fn pop_at_root_keeps_root_mode() {
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.pop_mode();
+        assert_eq!(state.mode, Mode::Workspace { id: None });
+    }
+
+    #[test]
+    fn pop_to_root_restores_bottom_of_stack() {
+        let mut state = state_in(Mode::Workspace { id: None });
+
+        state.push_view_workspace_mode(None);
+        state.push_mode(Mode::Picker);
+        state.pop_to_root_mode();
+
+        assert_eq!(state.mode, Mode::Workspace { id: None });
+        assert!(state.mode_history.is_empty());
+
+        state.pop_to_root_mode();
+        assert_eq!(state.mode, Mode::Workspace { id: None });
     }
 }
@@ -0,0 +1,164 @@
+use anyhow::Error;
+use iced::Task;
+use std::collections::VecDeque;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+use crate::state::{State, command::CommandMap, screen::TransientTool};
+
+// Oldest notifications are dropped once history reaches this size.
+pub const MAX_NOTIFICATIONS: usize = 100;
+
+// How long a notification is shown as a toast before it is only kept in history.
+pub const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    pub timestamp: OffsetDateTime,
+    pub dismissed: bool,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            timestamp: OffsetDateTime::now_utc(),
+            dismissed: false,
+        }
+    }
+
+    pub fn is_toast(&self, now: OffsetDateTime) -> bool {
+        !self.dismissed && now - self.timestamp < TOAST_DURATION
+    }
+}
+
+// Bounded notification history.
+//
+// Notifications which are neither dismissed nor expired are displayed as toasts.
+#[derive(Debug, Default)]
+pub struct Notifications {
+    history: VecDeque<Notification>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, notification: Notification) {
+        if self.history.len() >= MAX_NOTIFICATIONS {
+            self.history.pop_front();
+        }
+        self.history.push_back(notification);
+    }
+
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+        self.history.iter()
+    }
+
+    pub fn toasts(&self, now: OffsetDateTime) -> impl Iterator<Item = &Notification> {
+        self.history.iter().filter(move |n| n.is_toast(now))
+    }
+
+    pub fn has_toasts(&self, now: OffsetDateTime) -> bool {
+        self.toasts(now).next().is_some()
+    }
+
+    // Dismiss the most recent toast, returns false if there was nothing to dismiss.
+    pub fn dismiss_latest(&mut self, now: OffsetDateTime) -> bool {
+        if let Some(notification) = self.history.iter_mut().rev().find(|n| n.is_toast(now)) {
+            notification.dismissed = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.history
+            .iter()
+            .filter(|n| n.severity == Severity::Error)
+            .count()
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+pub trait NotificationActions {
+    fn push_error(&mut self, error: Error);
+    fn push_info(&mut self, message: impl Into<String>);
+    fn dismiss_notification(&mut self);
+    fn clear_notifications(&mut self);
+    fn toggle_notification_history(&mut self);
+}
+
+impl NotificationActions for State {
+    fn push_error(&mut self, error: Error) {
+        tracing::error!("{:#}", error);
+        self.notifications
+            .push(Notification::new(Severity::Error, error.to_string()));
+    }
+
+    fn push_info(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        tracing::info!("{}", message);
+        self.notifications
+            .push(Notification::new(Severity::Info, message));
+    }
+
+    fn dismiss_notification(&mut self) {
+        self.notifications.dismiss_latest(OffsetDateTime::now_utc());
+    }
+
+    fn clear_notifications(&mut self) {
+        self.notifications.clear();
+    }
+
+    fn toggle_notification_history(&mut self) {
+        self.screen_mut()
+            .toggle_transient_tool(TransientTool::Notifications);
+    }
+}
+
+pub fn notification_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_command(
+        "notifications-dismiss",
+        "Dismiss Notification",
+        "Dismiss the most recent notification toast",
+        |state: &mut State| {
+            state.dismiss_notification();
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_command(
+        "notifications-clear",
+        "Clear Notifications",
+        "Clear notification history",
+        |state: &mut State| {
+            state.clear_notifications();
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "notifications-history",
+        "Notification History",
+        "Toggle notification history tool",
+        |state: &mut State| {
+            state.toggle_notification_history();
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
@@ -0,0 +1,394 @@
+use anyhow::Result;
+use iced::Task;
+use iced::keyboard::{Key, Modifiers, key::Named};
+use thiserror::Error;
+
+use crate::state::{
+    State, Uid,
+    command::CommandMap,
+    mode::{Mode, ModeActions},
+    screen::Screen,
+    workspace::WorkspaceActions,
+};
+
+#[derive(Error, Debug)]
+pub enum OverviewError {
+    #[error("Overview is not open")]
+    NotOpen,
+    #[error("No workspace matches {filter:?}")]
+    NoMatch { filter: String },
+}
+
+// Every workspace as a card in a grid, shown while in overview mode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Overview {
+    // Cards are limited to workspaces with this in their name, ignoring case.
+    pub filter: String,
+    // Typed keys go to the filter instead of keymaps.
+    pub filtering: bool,
+    // Index into matching workspaces.
+    pub selected: usize,
+}
+
+impl Overview {
+    pub fn matches(&self, name: &str) -> bool {
+        name.to_lowercase().contains(&self.filter.to_lowercase())
+    }
+}
+
+// Grid is kept close to square, so cards stay readable with many workspaces.
+pub fn overview_columns(cards: usize) -> usize {
+    (cards as f64).sqrt().ceil().max(1.0) as usize
+}
+
+// Workspaces of all screens in screen order, as shown in the overview.
+pub fn overview_workspace_ids(state: &State, overview: &Overview) -> Vec<Uid> {
+    state
+        .screens
+        .iter()
+        .flat_map(|screen| &screen.workspace_ids)
+        .filter(|id| {
+            state
+                .workspaces
+                .get(id)
+                .is_some_and(|workspace| overview.matches(&workspace.name))
+        })
+        .copied()
+        .collect()
+}
+
+// Overview stays around after its mode is popped, but is no longer shown.
+pub fn shown_overview<'a>(state: &State, screen: &'a Screen) -> Option<&'a Overview> {
+    screen
+        .overview
+        .as_ref()
+        .filter(|_| state.mode == Mode::Overview)
+}
+
+fn open_overview(state: &mut State) -> Result<&mut Overview, OverviewError> {
+    if state.mode != Mode::Overview {
+        return Err(OverviewError::NotOpen);
+    }
+    state
+        .screen_mut()
+        .overview
+        .as_mut()
+        .ok_or(OverviewError::NotOpen)
+}
+
+pub trait OverviewActions {
+    fn show_overview(&mut self);
+    fn overview_move(&mut self, columns: isize, rows: isize) -> Result<()>;
+    fn start_overview_filter(&mut self) -> Result<()>;
+    fn overview_confirm(&mut self) -> Result<()>;
+    fn overview_key_pressed(&mut self, key: &Key, modifiers: Modifiers) -> bool;
+}
+
+impl OverviewActions for State {
+    // Focused workspace is selected initially. Overview is shown only on focused screen.
+    fn show_overview(&mut self) {
+        let overview = Overview::default();
+        let focused = self.current_workspace_id();
+        let selected = overview_workspace_ids(self, &overview)
+            .iter()
+            .position(|id| Some(*id) == focused)
+            .unwrap_or_default();
+
+        for screen in &mut self.screens {
+            screen.overview = None;
+        }
+        self.screen_mut().overview = Some(Overview {
+            selected,
+            ..overview
+        });
+        self.push_mode(Mode::Overview);
+    }
+
+    // Moves selection within the grid, stopping at its edges.
+    fn overview_move(&mut self, columns: isize, rows: isize) -> Result<()> {
+        let overview = open_overview(self)?.clone();
+        let cards = overview_workspace_ids(self, &overview).len();
+        if cards == 0 {
+            return Ok(());
+        }
+
+        let width = overview_columns(cards) as isize;
+        let (row, column) = (
+            overview.selected as isize / width,
+            overview.selected as isize % width,
+        );
+        let column = (column + columns).clamp(0, width - 1);
+        let row = (row + rows).clamp(0, (cards as isize - 1) / width);
+
+        open_overview(self)?.selected = (row * width + column).min(cards as isize - 1) as usize;
+        Ok(())
+    }
+
+    fn start_overview_filter(&mut self) -> Result<()> {
+        open_overview(self)?.filtering = true;
+        Ok(())
+    }
+
+    // Overview mode is left first, so focusing updates the mode below it.
+    fn overview_confirm(&mut self) -> Result<()> {
+        let overview = open_overview(self)?.clone();
+        let id = overview_workspace_ids(self, &overview)
+            .get(overview.selected)
+            .copied()
+            .ok_or_else(|| OverviewError::NoMatch {
+                filter: overview.filter.clone(),
+            })?;
+
+        self.pop_mode();
+        self.focus_workspace(id)
+    }
+
+    // While filtering, text edits the filter, enter keeps it and esc clears it.
+    fn overview_key_pressed(&mut self, key: &Key, modifiers: Modifiers) -> bool {
+        let Ok(overview) = open_overview(self) else {
+            return false;
+        };
+        if !overview.filtering || modifiers.command() || modifiers.control() || modifiers.alt() {
+            return false;
+        }
+
+        match key {
+            Key::Named(Named::Enter) => overview.filtering = false,
+            Key::Named(Named::Escape) => {
+                overview.filtering = false;
+                overview.filter.clear();
+            }
+            Key::Named(Named::Backspace) => {
+                overview.filter.pop();
+            }
+            Key::Named(Named::Space) => overview.filter.push(' '),
+            Key::Character(text) => overview.filter.push_str(text),
+            _ => return false,
+        }
+
+        overview.selected = 0;
+        true
+    }
+}
+
+pub fn overview_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_navigation_command(
+        "overview",
+        "Overview",
+        "Show every workspace to pick one",
+        |state: &mut State| {
+            state.show_overview();
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "overview-left",
+        "Left",
+        "Select workspace to the left",
+        |state: &mut State| {
+            state.overview_move(-1, 0)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "overview-right",
+        "Right",
+        "Select workspace to the right",
+        |state: &mut State| {
+            state.overview_move(1, 0)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "overview-up",
+        "Up",
+        "Select workspace above",
+        |state: &mut State| {
+            state.overview_move(0, -1)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "overview-down",
+        "Down",
+        "Select workspace below",
+        |state: &mut State| {
+            state.overview_move(0, 1)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "overview-filter",
+        "Filter",
+        "Type to show only workspaces with matching names",
+        |state: &mut State| {
+            state.start_overview_filter()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "overview-confirm",
+        "Focus Selected",
+        "Leave overview with selected workspace focused",
+        |state: &mut State| {
+            state.overview_confirm()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Harness;
+
+    fn with_named_workspaces(names: &[&str]) -> Harness {
+        let mut harness = Harness::with_workspaces(names.len());
+        for (id, name) in harness.workspace_ids().to_vec().into_iter().zip(names) {
+            harness.app.workspaces.get_mut(&id).unwrap().name = name.to_string();
+        }
+        harness
+    }
+
+    fn active(harness: &Harness) -> Option<&Overview> {
+        shown_overview(&harness.app, harness.app.screen())
+    }
+
+    fn selected(harness: &Harness) -> usize {
+        active(harness).unwrap().selected
+    }
+
+    #[test]
+    fn opening_selects_focused_workspace() {
+        let mut harness = with_named_workspaces(&["a", "b", "c"]);
+        let ids = harness.workspace_ids().to_vec();
+        harness.app.focus_workspace(ids[1]).unwrap();
+
+        harness.press("cmd-shift-w");
+
+        assert_eq!(harness.mode(), &Mode::Overview);
+        assert_eq!(selected(&harness), 1);
+    }
+
+    #[test]
+    fn selection_moves_within_grid() {
+        let mut harness = with_named_workspaces(&["a", "b", "c", "d", "e"]);
+        harness
+            .app
+            .focus_workspace(harness.workspace_ids()[0])
+            .unwrap();
+        harness.press("cmd-shift-w");
+
+        harness.press_all(&["l", "l", "l"]);
+        assert_eq!(selected(&harness), 2);
+
+        // Last row only has two cards, so moving down stops at the last one.
+        harness.press("j");
+        assert_eq!(selected(&harness), 4);
+
+        harness.press_all(&["k", "h", "left"]);
+        assert_eq!(selected(&harness), 0);
+
+        harness.press_all(&["down", "right"]);
+        assert_eq!(selected(&harness), 4);
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn typed_filter_limits_workspaces() {
+        let mut harness = with_named_workspaces(&["notes", "cinnabar", "Books"]);
+        harness.press("cmd-shift-w");
+
+        harness.press_all(&["/", "b", "o"]);
+        let overview = active(&harness).unwrap().clone();
+        assert_eq!(overview.filter, "bo");
+        assert_eq!(overview.selected, 0);
+        assert_eq!(
+            overview_workspace_ids(&harness.app, &overview),
+            vec![harness.workspace_ids()[2]]
+        );
+
+        harness.press_all(&["backspace", "enter"]);
+        let overview = active(&harness).unwrap();
+        assert_eq!(overview.filter, "b");
+        assert!(!overview.filtering);
+
+        // Keys go to keymaps again once filtering ends.
+        harness.press("l");
+        assert_eq!(active(&harness).unwrap().filter, "b");
+    }
+
+    #[test]
+    fn escape_clears_filter() {
+        let mut harness = with_named_workspaces(&["a", "b"]);
+        harness.press("cmd-shift-w");
+
+        harness.press_all(&["/", "a", "esc"]);
+
+        let overview = active(&harness).unwrap();
+        assert!(overview.filter.is_empty());
+        assert!(!overview.filtering);
+        assert_eq!(harness.mode(), &Mode::Overview);
+
+        harness.press("esc");
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        assert!(active(&harness).is_none());
+    }
+
+    #[test]
+    fn confirming_focuses_selected_workspace() {
+        let mut harness = with_named_workspaces(&["notes", "cinnabar", "books"]);
+        let ids = harness.workspace_ids().to_vec();
+        harness.press("cmd-shift-w");
+
+        harness.press_all(&["/", "c", "i", "enter", "enter"]);
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id: Some(ids[1]) });
+        assert_eq!(harness.focused(), Some(ids[1]));
+        assert!(harness.mode_history().is_empty());
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn confirming_without_matches_fails() {
+        let mut harness = with_named_workspaces(&["a"]);
+        harness.press("cmd-shift-w");
+
+        harness.press_all(&["/", "z", "enter", "enter"]);
+
+        assert_eq!(harness.mode(), &Mode::Overview);
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn overview_is_shown_only_on_its_screen() {
+        let mut harness = with_named_workspaces(&["a", "b"]);
+        harness.run("screen-create");
+
+        harness.press("cmd-shift-w");
+        let shown = |harness: &Harness| {
+            harness
+                .app
+                .screens
+                .iter()
+                .map(|screen| shown_overview(&harness.app, screen).is_some())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(&harness), vec![false, true]);
+
+        harness.press_all(&["esc", "cmd-o", "cmd-shift-w"]);
+        assert_eq!(harness.app.focused_screen, 0);
+        assert_eq!(shown(&harness), vec![true, false]);
+        assert_eq!(harness.errors(), 0);
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::Result;
+use iced::Task;
+use thiserror::Error;
+
+use crate::message::Message;
+use crate::state::{
+    State,
+    command::CommandMap,
+    mode::{Mode, ModeActions},
+    template::TemplateActions,
+    workspace::WorkspaceActions,
+};
+
+#[derive(Error, Debug)]
+pub enum PickerError {
+    #[error("Picker is not open")]
+    NotOpen,
+}
+
+// What happens with the item picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerAction {
+    WorkspaceTemplate,
+    // Items are workspaces of the focused screen, in order.
+    FocusWorkspace,
+}
+
+// List of items to choose one from, shown while in picker mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picker {
+    pub title: String,
+    pub items: Vec<String>,
+    pub selected: usize,
+    pub action: PickerAction,
+}
+
+impl Picker {
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected).map(String::as_str)
+    }
+}
+
+pub trait PickerActions {
+    fn open_picker(&mut self, title: impl Into<String>, items: Vec<String>, action: PickerAction);
+    fn picker_select(&mut self, offset: isize) -> Result<()>;
+    fn picker_confirm(&mut self) -> Result<Task<Message>>;
+    fn active_picker(&self) -> Option<&Picker>;
+    fn switch_workspace(&mut self) -> Result<()>;
+    fn confirm_workspace_switch(&mut self) -> Result<Task<Message>>;
+}
+
+impl PickerActions for State {
+    fn open_picker(&mut self, title: impl Into<String>, items: Vec<String>, action: PickerAction) {
+        self.picker = Some(Picker {
+            title: title.into(),
+            items,
+            selected: 0,
+            action,
+        });
+        self.push_mode(Mode::Picker);
+    }
+
+    fn picker_select(&mut self, offset: isize) -> Result<()> {
+        if self.mode != Mode::Picker {
+            return Err(PickerError::NotOpen.into());
+        }
+        let picker = self.picker.as_mut().ok_or(PickerError::NotOpen)?;
+
+        if !picker.items.is_empty() {
+            let len = picker.items.len() as isize;
+            picker.selected = (picker.selected as isize + offset).rem_euclid(len) as usize;
+        }
+
+        Ok(())
+    }
+
+    // Picker mode is left before the action runs, so it can push its own mode.
+    fn picker_confirm(&mut self) -> Result<Task<Message>> {
+        if self.mode != Mode::Picker {
+            return Err(PickerError::NotOpen.into());
+        }
+        let picker = self.picker.take().ok_or(PickerError::NotOpen)?;
+        self.pop_mode();
+
+        let Some(item) = picker.selected_item() else {
+            return Ok(Task::none());
+        };
+
+        match picker.action {
+            PickerAction::WorkspaceTemplate => {
+                self.create_workspace_from_template(item)?;
+            }
+            PickerAction::FocusWorkspace => {
+                if let Some(&id) = self.screen().workspace_ids.get(picker.selected) {
+                    self.focus_workspace(id)?;
+                }
+            }
+        }
+
+        Ok(Task::none())
+    }
+
+    // Picker stays around after its mode is popped, but is no longer shown.
+    fn active_picker(&self) -> Option<&Picker> {
+        self.picker.as_ref().filter(|_| self.mode == Mode::Picker)
+    }
+
+    // First use opens the switcher on the workspace after the focused one,
+    // following uses while it is open move on to the next.
+    fn switch_workspace(&mut self) -> Result<()> {
+        if self
+            .active_picker()
+            .is_some_and(|picker| picker.action == PickerAction::FocusWorkspace)
+        {
+            return self.picker_select(1);
+        }
+
+        let focused = self.current_workspace_id();
+        let ids = &self.screen().workspace_ids;
+        let selected = ids
+            .iter()
+            .position(|id| Some(*id) == focused)
+            .map_or(0, |index| index + 1);
+        let names = ids
+            .iter()
+            .filter_map(|id| self.workspaces.get(id))
+            .map(|workspace| workspace.name.clone())
+            .collect();
+
+        self.open_picker("Switch Workspace", names, PickerAction::FocusWorkspace);
+        self.picker_select(selected as isize)
+    }
+
+    // Does nothing for other pickers, so it can be bound to releasing a modifier.
+    fn confirm_workspace_switch(&mut self) -> Result<Task<Message>> {
+        if self
+            .active_picker()
+            .is_some_and(|picker| picker.action == PickerAction::FocusWorkspace)
+        {
+            return self.picker_confirm();
+        }
+
+        Ok(Task::none())
+    }
+}
+
+pub fn picker_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_navigation_command(
+        "picker-next",
+        "Next Item",
+        "Select next item in picker",
+        |state: &mut State| {
+            state.picker_select(1)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "picker-previous",
+        "Previous Item",
+        "Select previous item in picker",
+        |state: &mut State| {
+            state.picker_select(-1)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "picker-confirm",
+        "Confirm",
+        "Run picker action for selected item",
+        |state: &mut State| state.picker_confirm(),
+    );
+
+    commands.insert_navigation_command(
+        "workspace-switch",
+        "Switch Workspace",
+        "Pick workspace to focus, selecting next one while picking",
+        |state: &mut State| {
+            state.switch_workspace()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_navigation_command(
+        "workspace-switch-confirm",
+        "Confirm Switch",
+        "Focus workspace selected in workspace switcher",
+        |state: &mut State| state.confirm_workspace_switch(),
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Harness;
+
+    fn with_picker() -> Harness {
+        let mut harness = Harness::new();
+        harness.app.open_picker(
+            "Pick",
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            PickerAction::WorkspaceTemplate,
+        );
+        harness
+    }
+
+    fn selected(harness: &Harness) -> Option<&str> {
+        harness.app.active_picker().and_then(Picker::selected_item)
+    }
+
+    #[test]
+    fn selection_wraps_around() {
+        let mut harness = with_picker();
+
+        harness.press("up");
+        assert_eq!(selected(&harness), Some("c"));
+
+        harness.press_all(&["down", "down"]);
+        assert_eq!(selected(&harness), Some("b"));
+    }
+
+    #[test]
+    fn escape_closes_picker() {
+        let mut harness = with_picker();
+
+        harness.press("esc");
+
+        assert!(harness.mode_history().is_empty());
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        assert!(harness.app.active_picker().is_none());
+    }
+
+    #[test]
+    fn holding_alt_switches_workspace_on_release() {
+        let mut harness = Harness::new();
+        for _ in 0..3 {
+            harness.run("workspace-create");
+        }
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.press_all(&["alt-n", "alt-n"]);
+
+        assert_eq!(harness.mode(), &Mode::Picker);
+        assert_eq!(harness.app.active_picker().unwrap().selected, 1);
+
+        harness.release("alt");
+
+        assert_eq!(harness.focused(), Some(ids[1]));
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn releasing_alt_keeps_other_pickers_open() {
+        let mut harness = with_picker();
+
+        harness.release("alt");
+
+        assert_eq!(harness.mode(), &Mode::Picker);
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn commands_require_open_picker() {
+        let mut harness = Harness::new();
+
+        harness.run("picker-next").run("picker-confirm");
+
+        assert_eq!(harness.errors(), 2);
+    }
+}
@@ -0,0 +1,404 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use iced::mouse::ScrollDelta;
+use iced::{Point, Task, window};
+
+use crate::message::Message;
+use crate::state::command::CommandActions;
+use crate::state::macros::MacroActions;
+use crate::state::mode::ModeActions;
+use crate::state::screen::ScreenActions;
+use crate::state::tiling::Divider;
+use crate::state::workspace::WorkspaceActions;
+use crate::state::{State, Uid};
+
+// Second press on the same tile within this interval toggles fullscreen.
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+// Distance divider is dragged to move one unit of weight across it.
+pub const DIVIDER_STEP: f32 = 24.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DividerDrag {
+    // Window of the screen whose tiling is resized.
+    pub window: window::Id,
+    pub divider: Divider,
+    // Cursor position is only known once it moves after the press.
+    pub origin: Option<Point>,
+    pub steps: i32,
+}
+
+// Mouse buttons and wheel are translated into the same commands keyboard runs.
+#[derive(Debug, Default)]
+pub struct Pointer {
+    pub pressed: Option<Uid>,
+    pub last_press: Option<(Uid, Instant)>,
+    // Rows scrolled by wheel or touchpad which did not add up to a whole row yet.
+    pub scrolled: f32,
+    pub divider: Option<DividerDrag>,
+}
+
+pub trait PointerActions {
+    fn tile_pressed(&mut self, workspace_id: Uid, now: Instant) -> Result<Task<Message>>;
+    fn tile_released(&mut self, workspace_id: Uid) -> Result<Task<Message>>;
+    fn wheel_scrolled(&mut self, window: window::Id, delta: ScrollDelta) -> Result<Task<Message>>;
+    fn divider_pressed(&mut self, window: window::Id, divider: Divider);
+    fn pointer_moved(&mut self, window: window::Id, position: Point);
+    fn pointer_released(&mut self);
+    fn divider_released(&mut self);
+}
+
+impl PointerActions for State {
+    fn tile_pressed(&mut self, workspace_id: Uid, now: Instant) -> Result<Task<Message>> {
+        let is_double_click = self.pointer.last_press.is_some_and(|(id, at)| {
+            id == workspace_id && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+        });
+
+        self.pointer.pressed = Some(workspace_id);
+        self.focus_workspace(workspace_id)?;
+
+        if is_double_click {
+            self.pointer.last_press = None;
+            run_view_command(self, "view-toggle-fullscreen")
+        } else {
+            self.pointer.last_press = Some((workspace_id, now));
+            Ok(Task::none())
+        }
+    }
+
+    // Releasing over another tile moves pressed workspace to its place.
+    fn tile_released(&mut self, workspace_id: Uid) -> Result<Task<Message>> {
+        if self.pointer.divider.is_some() {
+            self.pointer_released();
+            return Ok(Task::none());
+        }
+
+        let Some(pressed_id) = self.pointer.pressed.take() else {
+            return Ok(Task::none());
+        };
+
+        let ids = &self.screen().workspace_ids;
+        let (Some(from), Some(to)) = (
+            ids.iter().position(|&id| id == pressed_id),
+            ids.iter().position(|&id| id == workspace_id),
+        ) else {
+            return Ok(Task::none());
+        };
+
+        if from == to {
+            return Ok(Task::none());
+        }
+
+        self.pointer.last_press = None;
+        self.focus_workspace(pressed_id)?;
+
+        let handle = if to > from {
+            "workspace-move-next"
+        } else {
+            "workspace-move-previous"
+        };
+
+        let mut tasks = Vec::new();
+        for _ in 0..from.abs_diff(to) {
+            tasks.push(self.run_command(handle)?);
+        }
+
+        Ok(Task::batch(tasks))
+    }
+
+    // Scrolls the view in any mode, a line of wheel scroll moves it by one row.
+    fn wheel_scrolled(&mut self, window: window::Id, delta: ScrollDelta) -> Result<Task<Message>> {
+        self.pointer.scrolled += match delta {
+            ScrollDelta::Lines { y, .. } => y,
+            ScrollDelta::Pixels { y, .. } => y / self.ui.config.collapsed_row_height,
+        };
+        let rows = self.pointer.scrolled.trunc();
+        self.pointer.scrolled -= rows;
+
+        let Some(index) = self
+            .screens
+            .iter()
+            .position(|screen| screen.window == Some(window))
+            .filter(|_| rows != 0.0)
+        else {
+            return Ok(Task::none());
+        };
+
+        // Wheel up moves content down, revealing rows above.
+        let handle = if rows > 0.0 {
+            "view-scroll-up"
+        } else {
+            "view-scroll-down"
+        };
+        let count = rows.abs() as usize;
+
+        // Screen under the cursor is scrolled without taking focus.
+        let focused_screen = std::mem::replace(&mut self.focused_screen, index);
+        let result = self.run_command_with_count(handle, Some(count));
+        self.focused_screen = focused_screen;
+
+        if result.is_ok() {
+            for _ in 0..count {
+                self.record_command(handle);
+            }
+        }
+        result
+    }
+
+    fn divider_pressed(&mut self, window: window::Id, divider: Divider) {
+        self.pointer.pressed = None;
+        self.pointer.divider = Some(DividerDrag {
+            window,
+            divider,
+            origin: None,
+            steps: 0,
+        });
+    }
+
+    // Cursor positions are relative to their window, so other windows are ignored.
+    fn pointer_moved(&mut self, window: window::Id, position: Point) {
+        let Some(drag) = self
+            .pointer
+            .divider
+            .as_mut()
+            .filter(|drag| drag.window == window)
+        else {
+            return;
+        };
+        let origin = *drag.origin.get_or_insert(position);
+
+        let distance = match drag.divider {
+            Divider::Column(_) => position.x - origin.x,
+            Divider::Row(_) => position.y - origin.y,
+        };
+        let steps = (distance / DIVIDER_STEP).trunc() as i32;
+
+        if steps != drag.steps {
+            let divider = drag.divider;
+            let delta = steps - drag.steps;
+            drag.steps = steps;
+
+            if let Some(screen) = self.screen_for_window_mut(window) {
+                screen.tiling.move_divider(divider, delta);
+            }
+        }
+    }
+
+    fn pointer_released(&mut self) {
+        self.pointer.pressed = None;
+        self.pointer.divider = None;
+    }
+
+    // Pressed tile is kept, as its own release may still be on the way.
+    fn divider_released(&mut self) {
+        self.pointer.divider = None;
+    }
+}
+
+// View commands only apply in view mode, so it is entered first.
+fn run_view_command(state: &mut State, handle: &str) -> Result<Task<Message>> {
+    let mode_task = if state.current_view_mode().is_none() {
+        state.run_command("view-mode")?
+    } else {
+        Task::none()
+    };
+
+    Ok(Task::batch([mode_task, state.run_command(handle)?]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::IpcHandle;
+    use crate::ipc::protocol::Event;
+    use crate::state::mode::{Mode, ViewMode};
+    use crate::state::tiling::DEFAULT_WEIGHT;
+    use crate::testing::Harness;
+
+    #[test]
+    fn press_focuses_tile_in_current_mode() {
+        let mut harness = Harness::with_workspaces(3);
+        let first = harness.workspace_ids()[0];
+
+        harness.click(first);
+
+        assert_eq!(harness.mode(), &Mode::Workspace { id: Some(first) });
+    }
+
+    #[test]
+    fn press_on_collapsed_tile_scrolls_it_into_view() {
+        let mut harness = Harness::with_workspaces(10);
+        let first = harness.workspace_ids()[0];
+        assert_eq!(harness.tiling().top_expanded_row_index, 2);
+
+        harness.click(first);
+
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+    }
+
+    #[test]
+    fn double_click_toggles_fullscreen() {
+        let mut harness = Harness::with_workspaces(2);
+        let first = harness.workspace_ids()[0];
+
+        harness.click(first).click(first);
+
+        assert!(harness.tiling().fullscreen);
+        assert_eq!(
+            harness.mode(),
+            &Mode::View {
+                mode: ViewMode::Workspace { id: Some(first) }
+            }
+        );
+    }
+
+    #[test]
+    fn slow_clicks_do_not_toggle_fullscreen() {
+        let mut harness = Harness::with_workspaces(1);
+        let id = harness.workspace_ids()[0];
+        let now = Instant::now();
+
+        let _ = harness.app.tile_pressed(id, now);
+        let _ = harness
+            .app
+            .tile_pressed(id, now + DOUBLE_CLICK_INTERVAL * 2);
+
+        assert!(!harness.tiling().fullscreen);
+    }
+
+    #[test]
+    fn drag_reorders_workspaces() {
+        let mut harness = Harness::with_workspaces(4);
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.drag(ids[0], ids[2]);
+
+        assert_eq!(harness.workspace_ids(), &[ids[1], ids[2], ids[0], ids[3]]);
+        assert_eq!(harness.focused(), Some(ids[0]));
+
+        harness.drag(ids[3], ids[1]);
+
+        assert_eq!(harness.workspace_ids(), &[ids[3], ids[1], ids[2], ids[0]]);
+    }
+
+    #[test]
+    fn release_without_press_is_ignored() {
+        let mut harness = Harness::with_workspaces(2);
+        let ids = harness.workspace_ids().to_vec();
+
+        let _ = harness.app.update(Message::TileReleased(ids[0]));
+
+        assert_eq!(harness.workspace_ids(), ids.as_slice());
+        assert_eq!(harness.focused(), Some(ids[1]));
+    }
+
+    #[test]
+    fn wheel_scrolls_view() {
+        let mut harness = Harness::with_workspaces(10);
+
+        harness.scroll(ScrollDelta::Lines { x: 0.0, y: 1.0 });
+
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        assert_eq!(harness.tiling().top_expanded_row_index, 1);
+
+        let row = harness.app.ui.config.collapsed_row_height;
+        harness.scroll(ScrollDelta::Pixels {
+            x: 0.0,
+            y: -row / 2.0,
+        });
+        assert_eq!(harness.tiling().top_expanded_row_index, 1);
+
+        harness.scroll(ScrollDelta::Pixels {
+            x: 0.0,
+            y: -row / 2.0,
+        });
+        assert_eq!(harness.tiling().top_expanded_row_index, 2);
+    }
+
+    #[test]
+    fn wheel_scroll_is_scaled_by_delta() {
+        let mut harness = Harness::with_workspaces(13);
+        assert_eq!(harness.tiling().top_expanded_row_index, 3);
+
+        harness.scroll(ScrollDelta::Lines { x: 0.0, y: 2.5 });
+        assert_eq!(harness.tiling().top_expanded_row_index, 1);
+
+        harness.scroll(ScrollDelta::Lines { x: 0.0, y: 0.5 });
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+    }
+
+    #[test]
+    fn wheel_runs_scroll_commands() {
+        let mut harness = Harness::with_workspaces(13);
+        let (ipc, mut events) = IpcHandle::subscribed();
+        harness.app.ipc = Some(ipc);
+
+        harness
+            .press("cmd-r")
+            .press("q")
+            .scroll(ScrollDelta::Lines { x: 0.0, y: 2.0 })
+            .press("cmd-shift-r");
+
+        let executed: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter_map(|event| match event {
+                Event::CommandExecuted { handle, .. } => Some(handle),
+                _ => None,
+            })
+            .collect();
+        assert!(executed.contains(&"view-scroll-up".to_string()));
+        assert_eq!(harness.app.macros.registers[&'q'], ["view-scroll-up"; 2]);
+        assert_eq!(harness.tiling().top_expanded_row_index, 1);
+
+        harness.press("cmd-w").press(".");
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+    }
+
+    #[test]
+    fn dragging_divider_moves_weight_between_columns() {
+        let mut harness = Harness::with_workspaces(3);
+        let ids = harness.workspace_ids().to_vec();
+
+        let window = harness.window();
+
+        let _ = harness
+            .app
+            .update(Message::DividerPressed(window, Divider::Column(0)));
+        for x in [100.0, 110.0, 100.0 + DIVIDER_STEP * 2.0] {
+            let _ = harness
+                .app
+                .update(Message::PointerMoved(window, Point::new(x, 10.0)));
+        }
+
+        assert_eq!(harness.tiling().column_weight(0), DEFAULT_WEIGHT + 2);
+        assert_eq!(harness.tiling().column_weight(1), DEFAULT_WEIGHT - 2);
+
+        // Releasing over a tile ends the drag without reordering workspaces.
+        let _ = harness.app.update(Message::TileReleased(ids[2]));
+        let _ = harness
+            .app
+            .update(Message::PointerMoved(window, Point::new(0.0, 10.0)));
+
+        assert_eq!(harness.tiling().column_weight(0), DEFAULT_WEIGHT + 2);
+        assert_eq!(harness.workspace_ids(), ids.as_slice());
+    }
+
+    #[test]
+    fn releasing_away_from_tiles_ends_divider_drag() {
+        let mut harness = Harness::with_workspaces(3);
+        let window = harness.window();
+
+        let _ = harness
+            .app
+            .update(Message::DividerPressed(window, Divider::Column(0)));
+        let _ = harness
+            .app
+            .update(Message::PointerMoved(window, Point::new(100.0, 10.0)));
+        let _ = harness.app.update(Message::DividerReleased);
+        let _ = harness
+            .app
+            .update(Message::PointerMoved(window, Point::new(200.0, 10.0)));
+
+        assert!(harness.app.pointer.divider.is_none());
+        assert_eq!(harness.tiling().column_weight(0), DEFAULT_WEIGHT);
+    }
+}
@@ -1,9 +1,404 @@
-use crate::state::{Uid, mode::Mode, tiling::Tiling};
+use anyhow::Result;
+use iced::{Task, window};
+use thiserror::Error;
+
+use crate::message::Message;
+use crate::state::{
+    State, Uid,
+    command::CommandMap,
+    mode::{Mode, ModeActions, ViewMode},
+    overview::Overview,
+    tiling::Tiling,
+    workspace::detach_workspace,
+};
+
+#[derive(Error, Debug)]
+pub enum ScreenError {
+    #[error("There is no other screen")]
+    NoOtherScreen,
+    #[error("Default target for screen action is not found")]
+    NoTarget,
+}
+
+// Built-in tools displayed on top of tiled items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransientTool {
+    Notifications,
+    Log,
+}
 
 #[derive(Default)]
 pub struct Screen {
+    // Window showing the screen, none until it is opened.
+    pub window: Option<window::Id>,
     pub workspace_ids: Vec<Uid>,
+    // Workspace focused when another screen took focus.
+    pub last_workspace_id: Option<Uid>,
     pub transient_tool_id: Option<Uid>,
+    pub transient_tool: Option<TransientTool>,
     pub tiling: Tiling,
-    pub mode: Mode,
+    // Overview opened on this screen, shown while in overview mode.
+    pub overview: Option<Overview>,
+}
+
+impl Screen {
+    pub fn toggle_transient_tool(&mut self, tool: TransientTool) {
+        if self.transient_tool == Some(tool) {
+            self.transient_tool = None;
+        } else {
+            self.transient_tool = Some(tool);
+        }
+    }
+}
+
+fn open_window() -> (window::Id, Task<Message>) {
+    let (id, task) = window::open(window::Settings::default());
+    (id, task.discard())
+}
+
+// Moves focus to another screen, restoring its focused workspace.
+pub fn switch_screen(state: &mut State, index: usize) -> Task<Message> {
+    if index == state.focused_screen {
+        return Task::none();
+    }
+
+    state.screen_mut().last_workspace_id = state.current_workspace_id();
+    state.focused_screen = index;
+
+    let screen = state.screen();
+    let workspace_id = screen
+        .last_workspace_id
+        .filter(|id| screen.workspace_ids.contains(id))
+        .or_else(|| screen.workspace_ids.first().copied());
+
+    if state.current_view_mode().is_some() {
+        state.update_view_workspace_mode(workspace_id);
+    } else {
+        state.update_workspace_mode(workspace_id);
+    }
+
+    // Modes below still point at workspaces of the previous screen.
+    let screen = &state.screens[index];
+    for mode in &mut state.mode_history {
+        let id = match mode {
+            Mode::Workspace { id } => id,
+            Mode::View {
+                mode: ViewMode::Workspace { id },
+            } => id,
+            _ => continue,
+        };
+        if id.is_some_and(|id| !screen.workspace_ids.contains(&id)) {
+            *id = workspace_id;
+        }
+    }
+
+    state
+        .screen()
+        .window
+        .map(window::gain_focus)
+        .unwrap_or_else(Task::none)
+}
+
+pub trait ScreenActions {
+    fn open_initial_screen(&mut self) -> Task<Message>;
+    fn create_screen(&mut self) -> Task<Message>;
+    fn focus_next_screen(&mut self) -> Result<Task<Message>>;
+    fn move_workspace_to_next_screen(&mut self) -> Result<()>;
+    fn close_screen(&mut self, window: window::Id) -> Task<Message>;
+    fn focus_screen_for_window(&mut self, window: window::Id);
+    fn screen_for_window(&self, window: window::Id) -> Option<&Screen>;
+    fn screen_for_window_mut(&mut self, window: window::Id) -> Option<&mut Screen>;
+}
+
+impl ScreenActions for State {
+    fn open_initial_screen(&mut self) -> Task<Message> {
+        let (id, task) = open_window();
+        self.screen_mut().window = Some(id);
+        task
+    }
+
+    fn create_screen(&mut self) -> Task<Message> {
+        let (id, task) = open_window();
+
+        self.screens.push(Screen {
+            window: Some(id),
+            tiling: self.layout.tiling(),
+            ..Screen::default()
+        });
+        // New window gains focus once it is opened.
+        let _ = switch_screen(self, self.screens.len() - 1);
+
+        task
+    }
+
+    fn focus_next_screen(&mut self) -> Result<Task<Message>> {
+        if self.screens.len() < 2 {
+            return Err(ScreenError::NoOtherScreen.into());
+        }
+
+        let next = (self.focused_screen + 1) % self.screens.len();
+        Ok(switch_screen(self, next))
+    }
+
+    // Workspace stays focused on the screen it is moved to.
+    fn move_workspace_to_next_screen(&mut self) -> Result<()> {
+        if self.screens.len() < 2 {
+            return Err(ScreenError::NoOtherScreen.into());
+        }
+
+        let workspace_id = self.current_workspace_id().ok_or(ScreenError::NoTarget)?;
+        detach_workspace(self, workspace_id)?;
+
+        let next = (self.focused_screen + 1) % self.screens.len();
+        let screen = &mut self.screens[next];
+        screen.workspace_ids.push(workspace_id);
+        screen.last_workspace_id = Some(workspace_id);
+
+        Ok(())
+    }
+
+    // Workspaces of a closed window are handed over to another screen.
+    fn close_screen(&mut self, window: window::Id) -> Task<Message> {
+        let Some(index) = self
+            .screens
+            .iter()
+            .position(|screen| screen.window == Some(window))
+        else {
+            return Task::none();
+        };
+
+        tracing::debug!(?window, index, "Close screen");
+
+        if self.screens.len() == 1 {
+            return iced::exit();
+        }
+
+        let task = if index == self.focused_screen {
+            switch_screen(self, (index + 1) % self.screens.len())
+        } else {
+            Task::none()
+        };
+
+        let closed = self.screens.remove(index);
+        if self.focused_screen > index {
+            self.focused_screen -= 1;
+        }
+        self.screen_mut().workspace_ids.extend(closed.workspace_ids);
+
+        task
+    }
+
+    // Window is already focused, so there is nothing to ask the platform for.
+    fn focus_screen_for_window(&mut self, window: window::Id) {
+        if let Some(index) = self
+            .screens
+            .iter()
+            .position(|screen| screen.window == Some(window))
+        {
+            let _ = switch_screen(self, index);
+        }
+    }
+
+    fn screen_for_window(&self, window: window::Id) -> Option<&Screen> {
+        self.screens
+            .iter()
+            .find(|screen| screen.window == Some(window))
+    }
+
+    fn screen_for_window_mut(&mut self, window: window::Id) -> Option<&mut Screen> {
+        self.screens
+            .iter_mut()
+            .find(|screen| screen.window == Some(window))
+    }
+}
+
+pub fn screen_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_command(
+        "screen-create",
+        "New Screen",
+        "Open a new screen in its own window",
+        |state: &mut State| Ok(state.create_screen()),
+    );
+
+    commands.insert_navigation_command(
+        "screen-focus-next",
+        "Next Screen",
+        "Focus on next screen",
+        |state: &mut State| state.focus_next_screen(),
+    );
+
+    commands.insert_command(
+        "workspace-move-to-next-screen",
+        "Move Workspace to Next Screen",
+        "Move current workspace to next screen",
+        |state: &mut State| {
+            state.move_workspace_to_next_screen()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Harness;
+
+    fn with_two_screens() -> Harness {
+        let mut harness = Harness::new();
+
+        harness
+            .run("workspace-create")
+            .run("workspace-create")
+            .run("screen-create");
+        harness
+    }
+
+    #[test]
+    fn create_focuses_empty_screen() {
+        let harness = with_two_screens();
+
+        assert_eq!(harness.app.screens.len(), 2);
+        assert_eq!(harness.app.focused_screen, 1);
+        assert!(harness.app.screen().window.is_some());
+        assert_eq!(harness.focused(), None);
+    }
+
+    #[test]
+    fn focus_next_restores_focused_workspace() {
+        let mut harness = with_two_screens();
+        let second = harness.app.screens[0].workspace_ids[1];
+
+        harness.run("screen-focus-next");
+
+        assert_eq!(harness.app.focused_screen, 0);
+        assert_eq!(harness.focused(), Some(second));
+    }
+
+    #[test]
+    fn focus_next_requires_another_screen() {
+        let mut harness = Harness::new();
+
+        harness
+            .run("screen-focus-next")
+            .run("workspace-move-to-next-screen");
+
+        assert_eq!(harness.errors(), 2);
+    }
+
+    #[test]
+    fn workspace_is_shown_on_one_screen() {
+        let mut harness = with_two_screens();
+        harness.run("screen-focus-next");
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.run("workspace-move-to-next-screen");
+
+        assert_eq!(harness.app.screens[0].workspace_ids, vec![ids[0]]);
+        assert_eq!(harness.app.screens[1].workspace_ids, vec![ids[1]]);
+        assert_eq!(harness.focused(), Some(ids[0]));
+
+        harness.run("screen-focus-next");
+        assert_eq!(harness.focused(), Some(ids[1]));
+    }
+
+    #[test]
+    fn clicking_workspace_on_another_screen_focuses_it() {
+        let mut harness = with_two_screens();
+        let first = harness.app.screens[0].workspace_ids[0];
+
+        harness.click(first);
+
+        assert_eq!(harness.app.focused_screen, 0);
+        assert_eq!(harness.focused(), Some(first));
+    }
+
+    #[test]
+    fn workspace_names_are_unique_across_screens() {
+        let mut harness = with_two_screens();
+
+        harness.run("workspace-create");
+
+        let names: Vec<_> = harness
+            .app
+            .screens
+            .iter()
+            .flat_map(|screen| &screen.workspace_ids)
+            .map(|id| harness.app.workspaces[id].name.as_str())
+            .collect();
+        assert_eq!(names, ["workspace 1", "workspace 2", "workspace 3"]);
+    }
+
+    #[test]
+    fn pointer_acts_on_screen_of_its_window() {
+        use crate::state::tiling::{DEFAULT_WEIGHT, Divider};
+        use iced::Point;
+        use iced::mouse::ScrollDelta;
+
+        let mut harness = Harness::new();
+        for _ in 0..10 {
+            harness.run("workspace-create");
+        }
+        harness.run("screen-create");
+        let window = harness.app.screens[0].window.unwrap();
+
+        let _ = harness.app.update(Message::WheelScrolled(
+            window,
+            ScrollDelta::Lines { x: 0.0, y: 1.0 },
+        ));
+        let _ = harness
+            .app
+            .update(Message::DividerPressed(window, Divider::Column(0)));
+        for x in [0.0, 100.0] {
+            let _ = harness
+                .app
+                .update(Message::PointerMoved(window, Point::new(x, 0.0)));
+        }
+
+        let tiling = &harness.app.screens[0].tiling;
+        assert_eq!(tiling.top_expanded_row_index, 1);
+        assert!(tiling.column_weight(0) > DEFAULT_WEIGHT);
+        assert!(harness.tiling().column_weights.is_empty());
+        assert_eq!(harness.app.focused_screen, 1);
+    }
+
+    #[test]
+    fn history_follows_focused_screen() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["c", "c", "cmd-shift-o", "c", "cmd-v", "cmd-o", "esc", "n"]);
+
+        let ids = harness.app.screens[0].workspace_ids.clone();
+        assert_eq!(harness.app.focused_screen, 0);
+        assert_eq!(harness.mode(), &Mode::Workspace { id: Some(ids[0]) });
+    }
+
+    #[test]
+    fn focusing_window_focuses_its_screen() {
+        let mut harness = with_two_screens();
+        let second = harness.app.screens[0].workspace_ids[1];
+        let window = harness.app.screens[0].window.unwrap();
+
+        let _ = harness.app.update(Message::WindowFocused(window));
+
+        assert_eq!(harness.app.focused_screen, 0);
+        assert_eq!(harness.focused(), Some(second));
+    }
+
+    #[test]
+    fn closing_window_hands_workspaces_over() {
+        let mut harness = with_two_screens();
+        harness.run("screen-focus-next");
+        let ids = harness.workspace_ids().to_vec();
+        let window = harness.app.screens[0].window.unwrap();
+
+        let _ = harness.app.update(Message::WindowClosed(window));
+
+        assert_eq!(harness.app.screens.len(), 1);
+        assert_eq!(harness.app.focused_screen, 0);
+        assert_eq!(harness.workspace_ids(), ids.as_slice());
+    }
 }
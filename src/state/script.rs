@@ -0,0 +1,477 @@
+use anyhow::{Context, Result};
+use iced::Task;
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
+use thiserror::Error;
+
+use crate::message::Message;
+use crate::state::{
+    State,
+    command::{Action, Command, CommandActions, CommandError},
+    mode::ModeActions,
+    notification::NotificationActions,
+    workspace::WorkspaceActions,
+};
+
+// Bounds keeping a runaway script from freezing the application or exhausting memory.
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum ScriptError {
+    #[error("Script {handle:?} failed to compile: {message}")]
+    Compile { handle: String, message: String },
+    #[error("Script {handle:?} failed: {message}")]
+    Runtime { handle: String, message: String },
+    #[error("Script {handle:?} is not loaded")]
+    NotLoaded { handle: String },
+    #[error("Mode {mode:?} cannot be pushed from a script")]
+    UnknownMode { mode: String },
+    #[error("Workspace {name:?} not found")]
+    WorkspaceNotFound { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptSource {
+    Inline(String),
+    File(PathBuf),
+}
+
+// Script declared in config, registered as a command under its handle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptConfig {
+    pub handle: String,
+    pub name: String,
+    pub description: String,
+    pub source: ScriptSource,
+}
+
+// Requests made by a running script, applied to state once it finishes.
+#[derive(Debug, Clone, PartialEq)]
+enum ScriptCall {
+    CreateWorkspace(String),
+    FocusWorkspace(String),
+    SetColumns(i64),
+    SetExpandedRows(i64),
+    PushMode(String),
+    Run(String),
+    Notify(String),
+}
+
+// Read-only view of state available to a running script.
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    workspaces: Vec<String>,
+    focused: Option<String>,
+    mode: String,
+}
+
+impl Snapshot {
+    fn of(state: &State) -> Self {
+        let name = |id| {
+            state
+                .workspaces
+                .get(id)
+                .map(|workspace| workspace.name.clone())
+        };
+
+        Self {
+            workspaces: state
+                .screen()
+                .workspace_ids
+                .iter()
+                .filter_map(name)
+                .collect(),
+            focused: state.current_workspace_id().as_ref().and_then(name),
+            mode: state.mode.label().to_string(),
+        }
+    }
+}
+
+// Scripts cannot touch state directly, they only see a snapshot of it
+// and queue calls which are checked and applied after they finish.
+pub struct Scripting {
+    engine: rhai::Engine,
+    scripts: HashMap<String, Rc<rhai::AST>>,
+    calls: Rc<RefCell<Vec<ScriptCall>>>,
+    snapshot: Rc<RefCell<Snapshot>>,
+}
+
+impl Default for Scripting {
+    fn default() -> Self {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let snapshot = Rc::new(RefCell::new(Snapshot::default()));
+
+        let mut engine = rhai::Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+            .set_max_string_size(MAX_STRING_SIZE)
+            .set_max_array_size(MAX_COLLECTION_SIZE)
+            .set_max_map_size(MAX_COLLECTION_SIZE)
+            .on_print(|message| tracing::info!(target: "script", "{message}"))
+            .on_debug(
+                |message, _, position| tracing::debug!(target: "script", %position, "{message}"),
+            );
+
+        let queue = |call: fn(String) -> ScriptCall| {
+            let calls = calls.clone();
+            move |value: &str| calls.borrow_mut().push(call(value.to_string()))
+        };
+        engine.register_fn("create_workspace", queue(ScriptCall::CreateWorkspace));
+        engine.register_fn("focus_workspace", queue(ScriptCall::FocusWorkspace));
+        engine.register_fn("push_mode", queue(ScriptCall::PushMode));
+        engine.register_fn("run", queue(ScriptCall::Run));
+        engine.register_fn("notify", queue(ScriptCall::Notify));
+
+        let queue = |call: fn(i64) -> ScriptCall| {
+            let calls = calls.clone();
+            move |value: i64| calls.borrow_mut().push(call(value))
+        };
+        engine.register_fn("set_columns", queue(ScriptCall::SetColumns));
+        engine.register_fn("set_expanded_rows", queue(ScriptCall::SetExpandedRows));
+
+        let view = snapshot.clone();
+        engine.register_fn("workspaces", move || -> rhai::Array {
+            view.borrow()
+                .workspaces
+                .iter()
+                .cloned()
+                .map(rhai::Dynamic::from)
+                .collect()
+        });
+        let view = snapshot.clone();
+        engine.register_fn("focused", move || -> rhai::Dynamic {
+            view.borrow()
+                .focused
+                .clone()
+                .map_or(rhai::Dynamic::UNIT, rhai::Dynamic::from)
+        });
+        let view = snapshot.clone();
+        engine.register_fn("mode", move || view.borrow().mode.clone());
+
+        Self {
+            engine,
+            scripts: HashMap::new(),
+            calls,
+            snapshot,
+        }
+    }
+}
+
+fn read_source(source: &ScriptSource) -> Result<String> {
+    match source {
+        ScriptSource::Inline(source) => Ok(source.clone()),
+        ScriptSource::File(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {}", path.display())),
+    }
+}
+
+// Positive count used for tiling dimensions.
+fn dimension(value: i64) -> usize {
+    usize::try_from(value).unwrap_or(0).max(1)
+}
+
+fn apply_call(state: &mut State, call: ScriptCall) -> Result<Task<Message>> {
+    tracing::debug!(?call, "Apply script call");
+
+    match call {
+        ScriptCall::CreateWorkspace(name) => {
+            let id = state.create_workspace();
+            if let Some(workspace) = state.workspaces.get_mut(&id) {
+                workspace.name = name;
+            }
+        }
+        // Same order as `workspaces()`, followed by workspaces of other screens.
+        ScriptCall::FocusWorkspace(name) => {
+            let id = state
+                .screen()
+                .workspace_ids
+                .iter()
+                .chain(
+                    state
+                        .screens
+                        .iter()
+                        .flat_map(|screen| &screen.workspace_ids),
+                )
+                .copied()
+                .find(|id| {
+                    state
+                        .workspaces
+                        .get(id)
+                        .is_some_and(|workspace| workspace.name == name)
+                })
+                .ok_or(ScriptError::WorkspaceNotFound { name })?;
+            state.focus_workspace(id)?;
+        }
+        ScriptCall::SetColumns(columns) => {
+            state.screen_mut().tiling.max_columns = dimension(columns);
+        }
+        ScriptCall::SetExpandedRows(rows) => {
+            state.screen_mut().tiling.max_expanded_rows = dimension(rows);
+        }
+        ScriptCall::PushMode(mode) => {
+            let workspace_id = state.current_workspace_id();
+            match mode.as_str() {
+                "workspace" => state.push_workspace_mode(workspace_id),
+                "view" => state.push_view_workspace_mode(workspace_id),
+                _ => return Err(ScriptError::UnknownMode { mode }.into()),
+            }
+        }
+        ScriptCall::Run(handle) => return state.run_command(&handle),
+        ScriptCall::Notify(message) => state.push_info(message),
+    }
+
+    Ok(Task::none())
+}
+
+pub trait ScriptActions {
+    fn load_scripts(&mut self, scripts: Vec<ScriptConfig>);
+    fn load_script(&mut self, script: ScriptConfig) -> Result<()>;
+    fn run_script(&mut self, handle: &str) -> Result<Task<Message>>;
+}
+
+impl ScriptActions for State {
+    // Scripts which fail to load are reported and skipped.
+    fn load_scripts(&mut self, scripts: Vec<ScriptConfig>) {
+        for script in scripts {
+            let handle = script.handle.clone();
+            if let Err(err) = self.load_script(script) {
+                self.push_error(err.context(format!("Failed to load script {handle:?}")));
+            }
+        }
+    }
+
+    fn load_script(&mut self, script: ScriptConfig) -> Result<()> {
+        if self.resolve_command(&script.handle).is_some() {
+            return Err(CommandError::HandleTaken {
+                handle: script.handle,
+            }
+            .into());
+        }
+
+        let source = read_source(&script.source)?;
+        let ast = self
+            .scripting
+            .engine
+            .compile(source)
+            .map_err(|err| ScriptError::Compile {
+                handle: script.handle.clone(),
+                message: err.to_string(),
+            })?;
+
+        self.scripting
+            .scripts
+            .insert(script.handle.clone(), Rc::new(ast));
+        self.insert_command(Command::new(
+            script.handle,
+            script.name,
+            script.description,
+            Action::Script,
+        ));
+
+        Ok(())
+    }
+
+    // Calls are applied in order and the first failing one stops the rest.
+    fn run_script(&mut self, handle: &str) -> Result<Task<Message>> {
+        let ast =
+            self.scripting
+                .scripts
+                .get(handle)
+                .cloned()
+                .ok_or_else(|| ScriptError::NotLoaded {
+                    handle: handle.to_string(),
+                })?;
+
+        *self.scripting.snapshot.borrow_mut() = Snapshot::of(self);
+        self.scripting.calls.borrow_mut().clear();
+
+        self.scripting
+            .engine
+            .run_ast(&ast)
+            .map_err(|err| ScriptError::Runtime {
+                handle: handle.to_string(),
+                message: err.to_string(),
+            })?;
+
+        let calls = std::mem::take(&mut *self.scripting.calls.borrow_mut());
+
+        let tasks = calls
+            .into_iter()
+            .map(|call| apply_call(self, call))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Task::batch(tasks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::mode::{Mode, ViewMode};
+    use crate::testing::Harness;
+
+    #[test]
+    fn script_is_registered_as_command() {
+        let harness = Harness::with_extra_config(
+            r#"
+[scripts.hello]
+name = "Hello"
+description = "Say hello"
+source = 'notify("hello")'
+"#,
+        );
+
+        let command = harness.app.commands.get_command("hello").unwrap();
+        assert_eq!(command.name(), "Hello");
+        assert_eq!(command.description(), "Say hello");
+    }
+
+    #[test]
+    fn script_changes_state_through_api() {
+        let mut harness = Harness::with_extra_config(
+            r#"
+[scripts.layout]
+source = '''
+for name in ["one", "two", "three"] {
+    create_workspace(name);
+}
+focus_workspace("two");
+set_columns(2);
+set_expanded_rows(1);
+push_mode("view");
+'''
+"#,
+        );
+
+        harness.run("layout");
+
+        let id = harness.focused().unwrap();
+        assert_eq!(harness.app.workspaces[&id].name, "two");
+        assert_eq!(harness.workspace_ids().len(), 3);
+        assert_eq!(harness.tiling().max_columns, 2);
+        assert_eq!(harness.tiling().max_expanded_rows, 1);
+        assert_eq!(
+            harness.mode(),
+            &Mode::View {
+                mode: ViewMode::Workspace { id: Some(id) }
+            }
+        );
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn script_reads_snapshot_and_runs_commands() {
+        let mut harness = Harness::with_extra_config(
+            r#"
+[scripts.next-or-create]
+source = '''
+if workspaces().len() < 2 {
+    run("workspace-create");
+} else if focused() != () && mode() == "WSP" {
+    run("workspace-next");
+}
+'''
+"#,
+        );
+
+        harness.run("next-or-create").run("next-or-create");
+        assert_eq!(harness.workspace_ids().len(), 2);
+        let second = harness.focused();
+
+        harness.run("next-or-create");
+        assert_ne!(harness.focused(), second);
+        assert_eq!(harness.workspace_ids().len(), 2);
+    }
+
+    #[test]
+    fn failing_call_stops_script() {
+        let mut harness = Harness::with_extra_config(
+            r#"
+[scripts.broken]
+source = '''
+focus_workspace("missing");
+create_workspace("never");
+'''
+"#,
+        );
+
+        harness.run("broken");
+
+        assert_eq!(harness.errors(), 1);
+        assert!(harness.workspace_ids().is_empty());
+    }
+
+    #[test]
+    fn runaway_script_is_stopped() {
+        let mut harness = Harness::with_extra_config(
+            r#"
+[scripts.forever]
+source = 'loop { create_workspace("x"); }'
+
+[scripts.recursive]
+source = 'run("recursive");'
+"#,
+        );
+
+        harness.run("forever").run("recursive");
+
+        assert_eq!(harness.errors(), 2);
+        assert!(harness.workspace_ids().is_empty());
+    }
+
+    #[test]
+    fn growing_script_is_stopped() {
+        let mut harness = Harness::with_extra_config(
+            r#"
+[scripts.string]
+source = 'let s = "x"; loop { s += s; }'
+
+[scripts.array]
+source = 'let a = [0]; loop { a += a; }'
+"#,
+        );
+
+        harness.run("string").run("array");
+
+        assert_eq!(harness.errors(), 2);
+    }
+
+    #[test]
+    fn focus_prefers_first_listed_workspace() {
+        let mut harness = Harness::with_extra_config(
+            r#"
+[scripts.duplicates]
+source = '''
+create_workspace("scratch");
+create_workspace("other");
+create_workspace("scratch");
+focus_workspace("scratch");
+'''
+"#,
+        );
+
+        harness.run("duplicates");
+
+        assert_eq!(harness.focused(), Some(harness.workspace_ids()[0]));
+    }
+
+    #[test]
+    fn invalid_scripts_are_reported() {
+        let harness = Harness::with_extra_config(
+            r#"
+[scripts.syntax]
+source = 'create_workspace('
+
+[scripts.workspace-create]
+source = 'notify("shadowed")'
+"#,
+        );
+
+        assert_eq!(harness.errors(), 2);
+        assert!(harness.app.commands.get_command("syntax").is_none());
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use iced::Task;
+use time::format_description::OwnedFormatItem;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::message::Message;
+use crate::state::State;
+
+pub const DEFAULT_CLOCK_FORMAT: &str = "[hour]:[minute]";
+pub const DEFAULT_SHELL_INTERVAL: Duration = Duration::from_secs(10);
+// Shell command still running after this long is killed and shown as failed.
+pub const SHELL_TIMEOUT: Duration = Duration::from_secs(5);
+
+static LOCAL_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
+
+// Local offset can only be determined soundly while the process is single threaded,
+// so it has to be captured at startup before any threads are spawned.
+pub fn init_local_offset() {
+    LOCAL_OFFSET.get_or_init(|| UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC));
+}
+
+pub fn local_offset() -> UtcOffset {
+    LOCAL_OFFSET.get().copied().unwrap_or(UtcOffset::UTC)
+}
+
+pub fn local_now() -> OffsetDateTime {
+    OffsetDateTime::now_utc().to_offset(local_offset())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    ModeStack,
+    Workspace,
+    PendingKeys,
+    Macro,
+    LastCommand,
+    ErrorCount,
+    // Format is parsed with the config, so a broken one is reported at load.
+    Clock { format: OwnedFormatItem },
+    Shell { command: String, interval: Duration },
+}
+
+impl Segment {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mode-stack" => Some(Self::ModeStack),
+            "workspace" => Some(Self::Workspace),
+            "pending-keys" => Some(Self::PendingKeys),
+            "macro" => Some(Self::Macro),
+            "last-command" => Some(Self::LastCommand),
+            "error-count" => Some(Self::ErrorCount),
+            "clock" => Some(Self::Clock {
+                format: default_clock_format(),
+            }),
+            _ => None,
+        }
+    }
+
+    // Whether segment content changes without any user input.
+    pub fn is_live(&self) -> bool {
+        matches!(self, Self::Clock { .. } | Self::Shell { .. })
+    }
+}
+
+pub fn default_clock_format() -> OwnedFormatItem {
+    time::format_description::parse_owned::<2>(DEFAULT_CLOCK_FORMAT)
+        .expect("default clock format is valid")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBarConfig {
+    pub left: Vec<Segment>,
+    pub center: Vec<Segment>,
+    pub right: Vec<Segment>,
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            left: vec![Segment::ModeStack],
+            center: Vec::new(),
+            right: vec![Segment::LastCommand],
+        }
+    }
+}
+
+impl StatusBarConfig {
+    pub fn segments(&self) -> impl Iterator<Item = &Segment> {
+        self.left.iter().chain(&self.center).chain(&self.right)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StatusBar {
+    pub config: StatusBarConfig,
+    pub shell_outputs: HashMap<String, String>,
+    shell_refreshed_at: HashMap<String, Instant>,
+    // Commands whose output has not arrived yet are not spawned again.
+    shell_running: HashSet<String>,
+}
+
+impl StatusBar {
+    pub fn new(config: StatusBarConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.config.segments().any(Segment::is_live)
+    }
+}
+
+pub trait StatusBarActions {
+    fn refresh_status_bar(&mut self) -> Task<Message>;
+    fn update_shell_segment(&mut self, command: String, output: String);
+}
+
+impl StatusBarActions for State {
+    // Spawns shell segment commands whose refresh interval has elapsed.
+    fn refresh_status_bar(&mut self) -> Task<Message> {
+        let now = Instant::now();
+        let status_bar = &mut self.status_bar;
+
+        let due: Vec<String> = status_bar
+            .config
+            .segments()
+            .filter_map(|segment| match segment {
+                Segment::Shell { command, interval }
+                    if !status_bar.shell_running.contains(command) =>
+                {
+                    status_bar
+                        .shell_refreshed_at
+                        .get(command)
+                        .is_none_or(|refreshed_at| now.duration_since(*refreshed_at) >= *interval)
+                        .then(|| command.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
+        let tasks = due.into_iter().map(|command| {
+            status_bar.shell_refreshed_at.insert(command.clone(), now);
+            status_bar.shell_running.insert(command.clone());
+
+            Task::perform(run_shell(command.clone()), move |output| {
+                Message::ShellSegmentOutput {
+                    command: command.clone(),
+                    output,
+                }
+            })
+        });
+
+        Task::batch(tasks.collect::<Vec<_>>())
+    }
+
+    fn update_shell_segment(&mut self, command: String, output: String) {
+        self.status_bar.shell_running.remove(&command);
+        self.status_bar.shell_outputs.insert(command, output);
+    }
+}
+
+async fn run_shell(command: String) -> String {
+    let output = tokio::time::timeout(
+        SHELL_TIMEOUT,
+        tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    match output {
+        Err(_) => {
+            tracing::warn!(%command, timeout = ?SHELL_TIMEOUT, "Status bar command timed out");
+            "ERR".to_string()
+        }
+        Ok(Ok(output)) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        Ok(Ok(output)) => {
+            tracing::warn!(%command, status = %output.status, "Status bar command failed");
+            "ERR".to_string()
+        }
+        Ok(Err(err)) => {
+            tracing::warn!(%command, %err, "Status bar command failed to start");
+            "ERR".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Harness;
+
+    fn with_shell_segment(interval: u64) -> Harness {
+        Harness::with_extra_config(&format!(
+            r#"
+            [status-bar]
+            left = [{{ segment = "shell", command = "date", interval = {interval} }}]
+            "#
+        ))
+    }
+
+    #[test]
+    fn running_command_is_not_spawned_again() {
+        let mut harness = with_shell_segment(0);
+        assert!(harness.app.status_bar.shell_running.contains("date"));
+        let refreshed_at = harness.app.status_bar.shell_refreshed_at["date"];
+
+        let _ = harness.app.refresh_status_bar();
+        assert_eq!(
+            harness.app.status_bar.shell_refreshed_at["date"],
+            refreshed_at
+        );
+
+        harness
+            .app
+            .update_shell_segment("date".to_string(), "today".to_string());
+        assert!(!harness.app.status_bar.shell_running.contains("date"));
+        assert_eq!(harness.app.status_bar.shell_outputs["date"], "today");
+
+        let _ = harness.app.refresh_status_bar();
+        assert!(harness.app.status_bar.shell_running.contains("date"));
+        assert!(harness.app.status_bar.shell_refreshed_at["date"] > refreshed_at);
+    }
+
+    #[test]
+    fn command_waits_for_its_interval() {
+        let mut harness = with_shell_segment(60);
+        let refreshed_at = harness.app.status_bar.shell_refreshed_at["date"];
+
+        harness
+            .app
+            .update_shell_segment("date".to_string(), "today".to_string());
+        let _ = harness.app.refresh_status_bar();
+
+        assert!(!harness.app.status_bar.shell_running.contains("date"));
+        assert_eq!(
+            harness.app.status_bar.shell_refreshed_at["date"],
+            refreshed_at
+        );
+    }
+}
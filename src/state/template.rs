@@ -0,0 +1,322 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use iced::Task;
+use thiserror::Error;
+
+use crate::state::{
+    State, Uid,
+    activity::Activity,
+    command::CommandMap,
+    notification::NotificationActions,
+    picker::{PickerAction, PickerActions},
+    tiling::Tiling,
+    workspace::{Workspace, WorkspaceActions, insert_workspace},
+};
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Workspace template {name:?} is not found")]
+    NotFound { name: String },
+    #[error("No workspace templates are configured")]
+    NoTemplates,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityTemplate {
+    pub name: String,
+    pub command: Option<String>,
+}
+
+// Tiling parameters which are not set keep their defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TilingTemplate {
+    pub max_columns: Option<usize>,
+    pub max_expanded_rows: Option<usize>,
+    pub column_weights: Option<Vec<u16>>,
+    pub row_weights: Option<Vec<u16>>,
+}
+
+impl TilingTemplate {
+    pub fn apply(&self, tiling: &mut Tiling) {
+        if let Some(columns) = self.max_columns {
+            tiling.max_columns = columns.max(1);
+        }
+        if let Some(rows) = self.max_expanded_rows {
+            tiling.max_expanded_rows = rows.max(1);
+        }
+        if let Some(weights) = &self.column_weights {
+            tiling.column_weights = weights.clone();
+        }
+        if let Some(weights) = &self.row_weights {
+            tiling.row_weights = weights.clone();
+        }
+    }
+
+    pub fn tiling(&self) -> Tiling {
+        let mut tiling = Tiling::default();
+        self.apply(&mut tiling);
+        tiling
+    }
+}
+
+// Template restoring every parameter of the tiling.
+impl From<&Tiling> for TilingTemplate {
+    fn from(tiling: &Tiling) -> Self {
+        Self {
+            max_columns: Some(tiling.max_columns),
+            max_expanded_rows: Some(tiling.max_expanded_rows),
+            column_weights: Some(tiling.column_weights.clone()),
+            row_weights: Some(tiling.row_weights.clone()),
+        }
+    }
+}
+
+// Describes a workspace created by `workspace-create-from-template` or on startup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceTemplate {
+    pub name: String,
+    pub workspace_name: Option<String>,
+    pub root: Option<PathBuf>,
+    pub tiling: TilingTemplate,
+    pub activities: Vec<ActivityTemplate>,
+}
+
+impl WorkspaceTemplate {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            workspace_name: None,
+            root: None,
+            tiling: TilingTemplate::default(),
+            activities: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StartupConfig {
+    // Templates of workspaces created on launch, in order.
+    pub workspaces: Vec<String>,
+}
+
+pub trait TemplateActions {
+    fn create_workspace_from_template(&mut self, name: &str) -> Result<Uid>;
+    fn pick_workspace_template(&mut self) -> Result<()>;
+    fn create_startup_workspaces(&mut self);
+}
+
+impl TemplateActions for State {
+    fn create_workspace_from_template(&mut self, name: &str) -> Result<Uid> {
+        let template = self
+            .templates
+            .iter()
+            .find(|template| template.name == name)
+            .cloned()
+            .ok_or_else(|| TemplateError::NotFound {
+                name: name.to_string(),
+            })?;
+
+        let mut workspace = Workspace {
+            name: template
+                .workspace_name
+                .unwrap_or_else(|| template.name.clone()),
+            ..Workspace::default()
+        };
+
+        template.tiling.apply(&mut workspace.tiling);
+
+        let activities: Vec<_> = template
+            .activities
+            .into_iter()
+            .map(|activity| Activity::new(activity.name, activity.command))
+            .collect();
+        workspace.activity_ids = activities.iter().map(|activity| activity.id).collect();
+
+        // Activities are only kept once their workspace is opened.
+        let workspace_id = match template.root {
+            Some(root) => self.open_workspace_with(root, workspace)?,
+            None => insert_workspace(self, workspace),
+        };
+        self.activities.extend(
+            activities
+                .into_iter()
+                .map(|activity| (activity.id, activity)),
+        );
+
+        Ok(workspace_id)
+    }
+
+    fn pick_workspace_template(&mut self) -> Result<()> {
+        if self.templates.is_empty() {
+            return Err(TemplateError::NoTemplates.into());
+        }
+
+        let names = self
+            .templates
+            .iter()
+            .map(|template| template.name.clone())
+            .collect();
+
+        self.open_picker(
+            "Create workspace from template",
+            names,
+            PickerAction::WorkspaceTemplate,
+        );
+
+        Ok(())
+    }
+
+    // Templates which fail are reported and skipped.
+    fn create_startup_workspaces(&mut self) {
+        for name in self.startup.workspaces.clone() {
+            if let Err(err) = self.create_workspace_from_template(&name) {
+                self.push_error(
+                    err.context(format!("Failed to create startup workspace {name:?}")),
+                );
+            }
+        }
+    }
+}
+
+pub fn template_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_command(
+        "workspace-create-from-template",
+        "Create Workspace from Template",
+        "Pick a template and create a workspace from it",
+        |state: &mut State| {
+            state.pick_workspace_template()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::mode::Mode;
+    use crate::testing::Harness;
+
+    fn with_templates(extra: &str) -> Harness {
+        Harness::with_extra_config(&format!(
+            r#"[templates.editor]
+name = "Editor"
+max-columns = 2
+max-expanded-rows = 1
+column-weights = [3, 1]
+activities = ["Shell", {{ name = "Build", command = "cargo watch" }}]
+
+[templates.scratch]
+{extra}"#
+        ))
+    }
+
+    #[test]
+    fn template_sets_up_workspace() {
+        let mut harness = with_templates("");
+
+        let id = harness
+            .app
+            .create_workspace_from_template("editor")
+            .unwrap();
+
+        let workspace = &harness.app.workspaces[&id];
+        assert_eq!(workspace.name, "Editor");
+        assert_eq!(workspace.tiling.max_columns, 2);
+        assert_eq!(workspace.tiling.max_expanded_rows, 1);
+        assert_eq!(workspace.tiling.column_weight(0), 3);
+        assert_eq!(harness.focused(), Some(id));
+
+        let activities: Vec<_> = workspace
+            .activity_ids
+            .iter()
+            .map(|id| &harness.app.activities[id])
+            .map(|activity| (activity.name.as_str(), activity.command.as_deref()))
+            .collect();
+        assert_eq!(
+            activities,
+            vec![("Shell", None), ("Build", Some("cargo watch"))]
+        );
+    }
+
+    #[test]
+    fn template_with_missing_root_keeps_no_activities() {
+        let mut harness =
+            with_templates("root = \"/nonexistent/cinnabar\"\nactivities = [\"Shell\"]");
+
+        assert!(
+            harness
+                .app
+                .create_workspace_from_template("scratch")
+                .is_err()
+        );
+        assert!(harness.app.activities.is_empty());
+    }
+
+    #[test]
+    fn deleting_workspace_removes_its_activities() {
+        let mut harness = with_templates("");
+        harness
+            .app
+            .create_workspace_from_template("editor")
+            .unwrap();
+
+        harness.press_all(&["cmd-w", "shift-c"]);
+
+        assert!(harness.workspace_ids().is_empty());
+        assert!(harness.app.activities.is_empty());
+    }
+
+    #[test]
+    fn unknown_template_is_an_error() {
+        let mut harness = with_templates("");
+
+        assert!(
+            harness
+                .app
+                .create_workspace_from_template("missing")
+                .is_err()
+        );
+        assert!(harness.workspace_ids().is_empty());
+    }
+
+    #[test]
+    fn picking_template_creates_workspace() {
+        let mut harness = with_templates("");
+
+        harness.press_all(&["cmd-w", "t", "down", "enter"]);
+
+        assert!(harness.mode_history().is_empty());
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        let id = harness.focused().unwrap();
+        assert_eq!(harness.app.workspaces[&id].name, "scratch");
+    }
+
+    #[test]
+    fn picking_without_templates_is_an_error() {
+        let mut harness = Harness::new();
+
+        harness.press_all(&["cmd-w", "t"]);
+
+        assert_eq!(harness.errors(), 1);
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+    }
+
+    #[test]
+    fn startup_creates_workspaces_in_order() {
+        let harness =
+            with_templates("[startup]\nworkspaces = [\"scratch\", \"missing\", \"editor\"]");
+
+        let names: Vec<_> = harness
+            .workspace_ids()
+            .iter()
+            .map(|id| harness.app.workspaces[id].name.as_str())
+            .collect();
+        assert_eq!(names, vec!["scratch", "Editor"]);
+        assert_eq!(harness.errors(), 1);
+    }
+}
@@ -0,0 +1,219 @@
+use iced::theme::Palette;
+use iced::{Color, Task, Theme};
+
+use crate::message::Message;
+use crate::state::{State, command::CommandMap};
+
+pub const SYSTEM_THEME: &str = "system";
+pub const CUSTOM_THEME: &str = "custom";
+
+// User palette from `[theme.custom]`.
+//
+// Focus border and collapsed tile colors are derived from the palette when not set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPalette {
+    pub palette: Palette,
+    pub focus_border: Option<Color>,
+    pub collapsed_tile: Option<Color>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThemeConfig {
+    pub name: Option<String>,
+    pub custom: Option<CustomPalette>,
+}
+
+// Colors of tiles which are not part of iced palette.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TileColors {
+    pub focus_border: Option<Color>,
+    pub collapsed_tile: Option<Color>,
+}
+
+// Themes available to `theme-cycle`, custom palette (if any) goes first.
+#[derive(Debug)]
+pub struct Themes {
+    themes: Vec<Theme>,
+    current: usize,
+    custom: Option<CustomPalette>,
+    // Theme is switched once desktop preference is detected, unless cycled before.
+    follows_system: bool,
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Self::from_config(ThemeConfig::default())
+    }
+}
+
+impl Themes {
+    pub fn from_config(config: ThemeConfig) -> Self {
+        let mut themes = Vec::new();
+
+        if let Some(custom) = &config.custom {
+            themes.push(Theme::custom("Custom".to_string(), custom.palette));
+        }
+        themes.extend(Theme::ALL.iter().cloned());
+
+        let selected = match config.name.as_deref() {
+            None | Some(SYSTEM_THEME) => None,
+            Some(CUSTOM_THEME) if config.custom.is_some() => Some(themes[0].clone()),
+            Some(name) => {
+                let theme = Theme::ALL
+                    .iter()
+                    .find(|theme| theme.to_string().eq_ignore_ascii_case(name))
+                    .cloned();
+                if theme.is_none() {
+                    tracing::warn!(name, "Unknown theme, following system preference");
+                }
+                theme
+            }
+        };
+        let follows_system = selected.is_none();
+        let selected = selected.unwrap_or(Theme::Light);
+
+        let current = themes
+            .iter()
+            .position(|theme| *theme == selected)
+            .unwrap_or(0);
+
+        Self {
+            themes,
+            current,
+            custom: config.custom,
+            follows_system,
+        }
+    }
+
+    pub fn follows_system(&self) -> bool {
+        self.follows_system
+    }
+
+    pub fn apply_system_theme(&mut self, dark: bool) {
+        if !self.follows_system {
+            return;
+        }
+
+        let theme = if dark { Theme::Dark } else { Theme::Light };
+        if let Some(index) = self.themes.iter().position(|t| *t == theme) {
+            self.current = index;
+        }
+    }
+
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+
+    pub fn cycle(&mut self) {
+        self.follows_system = false;
+        self.current = (self.current + 1) % self.themes.len();
+    }
+
+    pub fn tile_colors(&self) -> TileColors {
+        match &self.custom {
+            Some(custom) if self.current == 0 => TileColors {
+                focus_border: custom.focus_border,
+                collapsed_tile: custom.collapsed_tile,
+            },
+            _ => TileColors::default(),
+        }
+    }
+}
+
+// Best effort detection of desktop dark mode preference, light theme otherwise.
+//
+// Runs as a task, so a slow desktop query does not delay startup.
+async fn prefers_dark() -> bool {
+    if let Ok(gtk_theme) = std::env::var("GTK_THEME") {
+        return gtk_theme.to_lowercase().contains("dark");
+    }
+
+    let query = if cfg!(target_os = "macos") {
+        tokio::process::Command::new("defaults")
+            .args(["read", "-g", "AppleInterfaceStyle"])
+            .output()
+            .await
+    } else {
+        tokio::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+            .output()
+            .await
+    };
+
+    query
+        .map(|output| {
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout)
+                    .to_lowercase()
+                    .contains("dark")
+        })
+        .unwrap_or(false)
+}
+
+pub trait ThemeActions {
+    fn detect_system_theme(&self) -> Task<Message>;
+    fn cycle_theme(&mut self);
+}
+
+impl ThemeActions for State {
+    fn detect_system_theme(&self) -> Task<Message> {
+        if !self.themes.follows_system() {
+            return Task::none();
+        }
+
+        Task::perform(prefers_dark(), |dark| Message::SystemThemeDetected { dark })
+    }
+
+    fn cycle_theme(&mut self) {
+        self.themes.cycle();
+        tracing::debug!(theme = %self.themes.current(), "Theme changed");
+    }
+}
+
+pub fn theme_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_command(
+        "theme-cycle",
+        "Cycle Theme",
+        "Switch to the next available theme",
+        |state: &mut State| {
+            state.cycle_theme();
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Harness;
+
+    #[test]
+    fn detected_system_theme_is_applied() {
+        let mut harness = Harness::new();
+
+        let _ = harness
+            .app
+            .update(Message::SystemThemeDetected { dark: true });
+
+        assert_eq!(*harness.app.themes.current(), Theme::Dark);
+    }
+
+    #[test]
+    fn cycled_theme_is_kept_over_system_theme() {
+        let mut harness = Harness::new();
+
+        harness.press("cmd-t");
+        let cycled = harness.app.themes.current().clone();
+        let _ = harness
+            .app
+            .update(Message::SystemThemeDetected { dark: false });
+
+        assert_ne!(cycled, Theme::Light);
+        assert_eq!(*harness.app.themes.current(), cycled);
+        assert!(!harness.app.themes.follows_system());
+    }
+}
@@ -1,9 +1,16 @@
+// Weight of a column or row which was not resized, so it can also be shrunk.
+pub const DEFAULT_WEIGHT: u16 = 4;
+pub const MAX_WEIGHT: u16 = 16;
+
 #[derive(Debug)]
 pub struct Tiling {
     pub max_expanded_rows: usize,
     pub max_columns: usize,
     pub top_expanded_row_index: usize,
     pub fullscreen: bool,
+    // Proportions of columns and rows, missing entries have default weight.
+    pub column_weights: Vec<u16>,
+    pub row_weights: Vec<u16>,
 }
 
 impl Default for Tiling {
@@ -13,6 +20,303 @@ impl Default for Tiling {
             max_columns: 3,
             top_expanded_row_index: 0,
             fullscreen: false,
+            column_weights: Vec::new(),
+            row_weights: Vec::new(),
+        }
+    }
+}
+
+// Boundary between a column or row and the next one, which can be dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Divider {
+    Column(usize),
+    Row(usize),
+}
+
+fn weight(weights: &[u16], index: usize) -> u16 {
+    weights.get(index).copied().unwrap_or(DEFAULT_WEIGHT)
+}
+
+fn weight_mut(weights: &mut Vec<u16>, index: usize) -> &mut u16 {
+    if weights.len() <= index {
+        weights.resize(index + 1, DEFAULT_WEIGHT);
+    }
+    &mut weights[index]
+}
+
+fn resize(weights: &mut Vec<u16>, index: usize, delta: i32) {
+    let weight = weight_mut(weights, index);
+    *weight = (i32::from(*weight) + delta).clamp(1, i32::from(MAX_WEIGHT)) as u16;
+}
+
+// Moves weight from one side of the divider to the other, keeping their sum.
+fn move_divider(weights: &mut Vec<u16>, index: usize, steps: i32) {
+    for _ in 0..steps.unsigned_abs() {
+        let (grown, shrunk) = if steps > 0 {
+            (index, index + 1)
+        } else {
+            (index + 1, index)
+        };
+
+        if weight(weights, grown) >= MAX_WEIGHT || weight(weights, shrunk) <= 1 {
+            break;
+        }
+        *weight_mut(weights, grown) += 1;
+        *weight_mut(weights, shrunk) -= 1;
+    }
+}
+
+// How a tile is drawn. Collapsed tiles next to expanded rows show their title,
+// the ones further away are folded into a thin strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    Expanded,
+    Collapsed,
+    Hidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TilePlacement {
+    pub placement: Placement,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Tiling {
+    pub fn columns(&self) -> usize {
+        self.max_columns.max(1)
+    }
+
+    pub fn row_count(&self, items: usize) -> usize {
+        items.div_ceil(self.columns())
+    }
+
+    // Last row which can be the top expanded one without leaving expanded rows empty.
+    pub fn max_top_row(&self, items: usize) -> usize {
+        self.row_count(items)
+            .saturating_sub(self.max_expanded_rows.max(1))
+    }
+
+    // Stored index may be out of range after items are removed or rows are expanded.
+    pub fn top_row(&self, items: usize) -> usize {
+        self.top_expanded_row_index.min(self.max_top_row(items))
+    }
+
+    pub fn layout(&self, items: usize, focused: Option<usize>) -> Vec<TilePlacement> {
+        let columns = self.columns();
+        let top_row = self.top_row(items);
+        let bottom_row = top_row + self.max_expanded_rows.max(1);
+
+        (0..items)
+            .map(|index| {
+                let row = index / columns;
+
+                let placement = if self.fullscreen {
+                    if focused == Some(index) {
+                        Placement::Expanded
+                    } else {
+                        Placement::Hidden
+                    }
+                } else if (top_row..bottom_row).contains(&row) {
+                    Placement::Expanded
+                } else if row + 1 == top_row || row == bottom_row {
+                    Placement::Collapsed
+                } else {
+                    Placement::Hidden
+                };
+
+                TilePlacement {
+                    placement,
+                    row,
+                    column: index % columns,
+                }
+            })
+            .collect()
+    }
+
+    pub fn scroll_down(&mut self, items: usize) {
+        self.top_expanded_row_index = (self.top_row(items) + 1).min(self.max_top_row(items));
+    }
+
+    pub fn scroll_up(&mut self, items: usize) {
+        self.top_expanded_row_index = self.top_row(items).saturating_sub(1);
+    }
+
+    pub fn column_weight(&self, column: usize) -> u16 {
+        weight(&self.column_weights, column)
+    }
+
+    pub fn row_weight(&self, row: usize) -> u16 {
+        weight(&self.row_weights, row)
+    }
+
+    pub fn resize_column(&mut self, column: usize, delta: i32) {
+        resize(&mut self.column_weights, column, delta);
+    }
+
+    pub fn resize_row(&mut self, row: usize, delta: i32) {
+        resize(&mut self.row_weights, row, delta);
+    }
+
+    // Positive steps move divider right or down.
+    pub fn move_divider(&mut self, divider: Divider, steps: i32) {
+        match divider {
+            Divider::Column(column) => move_divider(&mut self.column_weights, column, steps),
+            Divider::Row(row) => move_divider(&mut self.row_weights, row, steps),
+        }
+    }
+
+    pub fn equalize(&mut self) {
+        self.column_weights.clear();
+        self.row_weights.clear();
+    }
+
+    // Scrolls as little as possible for the item to land in expanded rows.
+    pub fn reveal(&mut self, index: usize, items: usize) {
+        if index >= items {
+            return;
+        }
+
+        let row = index / self.columns();
+        let top_row = self.top_row(items);
+        let expanded_rows = self.max_expanded_rows.max(1);
+
+        self.top_expanded_row_index = if row < top_row {
+            row
+        } else if row >= top_row + expanded_rows {
+            row + 1 - expanded_rows
+        } else {
+            top_row
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn tiling() -> impl Strategy<Value = Tiling> {
+        (0..5usize, 0..6usize, 0..12usize, any::<bool>()).prop_map(
+            |(max_expanded_rows, max_columns, top_expanded_row_index, fullscreen)| Tiling {
+                max_expanded_rows,
+                max_columns,
+                top_expanded_row_index,
+                fullscreen,
+                ..Tiling::default()
+            },
+        )
+    }
+
+    fn items_and_index() -> impl Strategy<Value = (usize, usize)> {
+        (1..40usize).prop_flat_map(|items| (Just(items), 0..items))
+    }
+
+    proptest! {
+        #[test]
+        fn every_item_is_placed_exactly_once(tiling in tiling(), items in 0..40usize) {
+            let layout = tiling.layout(items, None);
+            let mut cells: Vec<_> = layout.iter().map(|tile| (tile.row, tile.column)).collect();
+            cells.sort();
+            cells.dedup();
+
+            prop_assert_eq!(layout.len(), items);
+            prop_assert_eq!(cells.len(), items);
+        }
+
+        #[test]
+        fn tiles_fill_rows_in_order(tiling in tiling(), items in 0..40usize) {
+            for (index, tile) in tiling.layout(items, None).iter().enumerate() {
+                prop_assert!(tile.column < tiling.columns());
+                prop_assert_eq!(tile.row * tiling.columns() + tile.column, index);
+            }
+        }
+
+        #[test]
+        fn expanded_rows_are_filled(tiling in tiling(), items in 1..40usize) {
+            prop_assume!(!tiling.fullscreen);
+
+            let expanded_rows = tiling
+                .layout(items, None)
+                .iter()
+                .filter(|tile| tile.placement == Placement::Expanded)
+                .map(|tile| tile.row)
+                .max()
+                .map(|row| row + 1 - tiling.top_row(items));
+
+            let expected = tiling.max_expanded_rows.max(1).min(tiling.row_count(items));
+            prop_assert_eq!(expanded_rows, Some(expected));
+        }
+
+        #[test]
+        fn revealed_item_is_expanded(mut tiling in tiling(), (items, index) in items_and_index()) {
+            tiling.reveal(index, items);
+
+            let layout = tiling.layout(items, Some(index));
+            prop_assert_eq!(layout[index].placement, Placement::Expanded);
+        }
+
+        #[test]
+        fn fullscreen_expands_only_focused(mut tiling in tiling(), (items, index) in items_and_index()) {
+            tiling.fullscreen = true;
+
+            for (position, tile) in tiling.layout(items, Some(index)).iter().enumerate() {
+                prop_assert_eq!(tile.placement == Placement::Expanded, position == index);
+            }
+        }
+
+        #[test]
+        fn scrolling_stays_in_range(mut tiling in tiling(), items in 0..40usize, down in any::<bool>()) {
+            if down {
+                tiling.scroll_down(items);
+            } else {
+                tiling.scroll_up(items);
+            }
+
+            prop_assert!(tiling.top_expanded_row_index <= tiling.max_top_row(items));
+        }
+    }
+
+    #[test]
+    fn resize_stays_within_bounds() {
+        let mut tiling = Tiling::default();
+
+        tiling.resize_column(2, 100);
+        tiling.resize_row(1, -100);
+
+        assert_eq!(tiling.column_weight(2), MAX_WEIGHT);
+        assert_eq!(tiling.column_weight(0), DEFAULT_WEIGHT);
+        assert_eq!(tiling.row_weight(1), 1);
+    }
+
+    #[test]
+    fn equalize_restores_default_weights() {
+        let mut tiling = Tiling::default();
+
+        tiling.resize_column(0, 3);
+        tiling.resize_row(0, -2);
+        tiling.equalize();
+
+        assert_eq!(tiling.column_weight(0), DEFAULT_WEIGHT);
+        assert_eq!(tiling.row_weight(0), DEFAULT_WEIGHT);
+    }
+
+    proptest! {
+        #[test]
+        fn divider_keeps_total_weight(index in 0..4usize, steps in -20..20i32, column in any::<bool>()) {
+            let mut tiling = Tiling::default();
+            let (divider, weight): (_, fn(&Tiling, usize) -> u16) = if column {
+                (Divider::Column(index), Tiling::column_weight)
+            } else {
+                (Divider::Row(index), Tiling::row_weight)
+            };
+
+            tiling.move_divider(divider, steps);
+
+            let left = weight(&tiling, index);
+            let right = weight(&tiling, index + 1);
+            prop_assert_eq!(left + right, 2 * DEFAULT_WEIGHT);
+            prop_assert!((1..=MAX_WEIGHT).contains(&left) && (1..=MAX_WEIGHT).contains(&right));
         }
     }
 }
@@ -0,0 +1,234 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+use std::time::{Duration, Instant};
+
+use crate::state::{
+    State,
+    keymap::{Trigger, resolve_trigger},
+    mode::Mode,
+};
+
+// Key kept down this long runs its `hold` binding instead of its `tap` one.
+pub const HOLD_DURATION: Duration = Duration::from_millis(300);
+// Bounds how late a `hold` binding runs after `HOLD_DURATION` passes.
+pub const HOLD_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+// Key with a tap or hold binding, from its press until its release.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldKey {
+    pub keybind: (Key, Modifiers),
+    pub since: Instant,
+    // Hold binding already ran, so releasing the key does nothing.
+    pub fired: bool,
+}
+
+// Released modifier is reported as a key, but keybinds write it as a modifier alone.
+pub fn released_keybind(key: &Key, modifiers: Modifiers) -> (Key, Modifiers) {
+    let modifier = match key {
+        Key::Named(Named::Alt) => Modifiers::ALT,
+        Key::Named(Named::Control) => Modifiers::CTRL,
+        Key::Named(Named::Shift) => Modifiers::SHIFT,
+        Key::Named(Named::Super | Named::Meta) => Modifiers::LOGO,
+        _ => return (key.clone(), modifiers),
+    };
+
+    (Key::Unidentified, modifiers | modifier)
+}
+
+pub trait TriggerActions {
+    fn key_held(&mut self, key: &Key, modifiers: Modifiers, now: Instant) -> bool;
+    fn hold_elapsed(&mut self, now: Instant) -> Option<String>;
+    fn key_released(&mut self, key: &Key, modifiers: Modifiers, now: Instant) -> Option<String>;
+    fn waits_for_hold(&self) -> bool;
+}
+
+impl TriggerActions for State {
+    // Keys with a tap or hold binding wait for release instead of running on press.
+    fn key_held(&mut self, key: &Key, modifiers: Modifiers, now: Instant) -> bool {
+        let keybind = (key.clone(), modifiers);
+
+        // Repeated press of a key kept down.
+        if self
+            .held_key
+            .as_ref()
+            .is_some_and(|held| held.keybind == keybind)
+        {
+            return true;
+        }
+
+        let has_binding = [Trigger::Tap, Trigger::Hold]
+            .into_iter()
+            .any(|trigger| resolve_trigger(self, trigger, keybind.clone()).is_some());
+        if has_binding {
+            self.held_key = Some(HeldKey {
+                keybind,
+                since: now,
+                fired: false,
+            });
+        }
+
+        has_binding
+    }
+
+    fn hold_elapsed(&mut self, now: Instant) -> Option<String> {
+        let held = self
+            .held_key
+            .as_mut()
+            .filter(|held| !held.fired && now.duration_since(held.since) >= HOLD_DURATION)?;
+        held.fired = true;
+
+        let keybind = held.keybind.clone();
+        resolve_trigger(self, Trigger::Hold, keybind)
+    }
+
+    // Releasing a held key runs its tap or hold binding, other keys their release binding.
+    // Keys captured for a new binding run nothing, as on their press.
+    fn key_released(&mut self, key: &Key, modifiers: Modifiers, now: Instant) -> Option<String> {
+        if self.mode == Mode::KeyCapture {
+            self.held_key = None;
+            return None;
+        }
+        if let Some(held) = self.held_key.take_if(|held| held.keybind.0 == *key) {
+            if held.fired {
+                return None;
+            }
+            let trigger = if now.duration_since(held.since) >= HOLD_DURATION {
+                Trigger::Hold
+            } else {
+                Trigger::Tap
+            };
+            return resolve_trigger(self, trigger, held.keybind);
+        }
+
+        resolve_trigger(self, Trigger::Release, released_keybind(key, modifiers))
+    }
+
+    fn waits_for_hold(&self) -> bool {
+        self.held_key.as_ref().is_some_and(|held| !held.fired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_keybind;
+    use crate::state::mode::ModeActions;
+    use crate::testing::Harness;
+
+    fn with_triggers() -> Harness {
+        Harness::with_extra_config(
+            "[keymaps.triggers]\nextends = \"view-mode\"\n\
+             workspace-create = \"release-c\"\npop-mode = \"tap-space\"\n\
+             view-equalize = \"hold-space\"\n[mode-keymaps]\nview = \"triggers\"\n",
+        )
+    }
+
+    fn space() -> Key {
+        Key::Named(Named::Space)
+    }
+
+    #[test]
+    fn release_binding_runs_on_release() {
+        let mut harness = with_triggers();
+
+        harness.press_all(&["cmd-v", "c"]);
+        assert_eq!(harness.workspace_ids().len(), 0);
+
+        harness.release("c");
+        assert_eq!(harness.workspace_ids().len(), 1);
+    }
+
+    #[test]
+    fn release_binding_is_ignored_while_capturing_key() {
+        let mut harness = Harness::with_extra_config(
+            "[keymaps.key-capture-mode]\nworkspace-create = \"release-c\"\n",
+        );
+        harness.app.push_mode(Mode::KeyCapture);
+        let c = parse_keybind(vec!["c"]);
+        assert_eq!(
+            resolve_trigger(&harness.app, Trigger::Release, c).as_deref(),
+            Some("workspace-create")
+        );
+
+        harness.release("c");
+
+        assert_eq!(harness.workspace_ids().len(), 0);
+    }
+
+    #[test]
+    fn short_press_runs_tap_binding() {
+        let mut harness = with_triggers();
+
+        harness.press_all(&["cmd-v", "space"]);
+        assert!(matches!(harness.mode(), Mode::View { .. }));
+
+        harness.release("space");
+        assert!(matches!(harness.mode(), Mode::Workspace { .. }));
+        assert!(harness.app.held_key.is_none());
+    }
+
+    #[test]
+    fn held_key_runs_hold_binding_once() {
+        let mut harness = with_triggers();
+        let now = Instant::now();
+        harness.press("cmd-v");
+
+        assert!(harness.app.key_held(&space(), Modifiers::empty(), now));
+        assert!(harness.app.key_held(&space(), Modifiers::empty(), now));
+        assert_eq!(harness.app.hold_elapsed(now + HOLD_DURATION / 2), None);
+        assert!(harness.app.waits_for_hold());
+
+        assert_eq!(
+            harness.app.hold_elapsed(now + HOLD_DURATION).as_deref(),
+            Some("view-equalize")
+        );
+        assert_eq!(harness.app.hold_elapsed(now + HOLD_DURATION * 2), None);
+        assert_eq!(
+            harness
+                .app
+                .key_released(&space(), Modifiers::empty(), now + HOLD_DURATION * 2),
+            None
+        );
+    }
+
+    #[test]
+    fn late_release_counts_as_hold() {
+        let mut harness = with_triggers();
+        let now = Instant::now();
+        harness.press("cmd-v");
+
+        harness.app.key_held(&space(), Modifiers::empty(), now);
+
+        assert_eq!(
+            harness
+                .app
+                .key_released(&space(), Modifiers::empty(), now + HOLD_DURATION)
+                .as_deref(),
+            Some("view-equalize")
+        );
+    }
+
+    #[test]
+    fn keys_without_tap_or_hold_binding_are_not_held() {
+        let mut harness = with_triggers();
+
+        harness.press("space");
+
+        assert!(harness.app.held_key.is_none());
+    }
+
+    #[test]
+    fn released_modifier_is_written_as_modifier() {
+        assert_eq!(
+            released_keybind(&Key::Named(Named::Alt), Modifiers::empty()),
+            parse_keybind(vec!["alt"])
+        );
+        assert_eq!(
+            released_keybind(&Key::Named(Named::Shift), Modifiers::ALT),
+            parse_keybind(vec!["alt", "shift"])
+        );
+        assert_eq!(
+            released_keybind(&space(), Modifiers::ALT),
+            parse_keybind(vec!["alt", "space"])
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use iced::{Font, Task};
+use thiserror::Error;
+
+use crate::state::{State, command::CommandMap};
+
+pub const DEFAULT_FONT: &str = "Iosevka Nerd Font";
+
+pub const ZOOM_STEP: f64 = 0.1;
+pub const MIN_ZOOM: f64 = 0.5;
+pub const MAX_ZOOM: f64 = 3.0;
+
+#[derive(Error, Debug)]
+pub enum UiError {
+    #[error("Zoom is already at its limit of {zoom:.1}")]
+    ZoomLimit { zoom: f64 },
+}
+
+// UI settings from `[ui]`, sizes are in logical pixels before zoom is applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UiConfig {
+    pub font: Font,
+    pub monospace_font: Font,
+    pub font_size: f32,
+    pub tile_padding: f32,
+    pub tile_gap: f32,
+    pub border_width: f32,
+    pub collapsed_row_height: f32,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            font: Font::with_name(DEFAULT_FONT),
+            monospace_font: Font::MONOSPACE,
+            font_size: 16.0,
+            tile_padding: 10.0,
+            tile_gap: 0.0,
+            border_width: 1.0,
+            collapsed_row_height: 50.0,
+        }
+    }
+}
+
+// Font names have to outlive the application, fonts are configured once per run.
+pub fn font_with_name(name: &str) -> Font {
+    Font::with_name(Box::leak(name.to_string().into_boxed_str()))
+}
+
+#[derive(Debug)]
+pub struct Ui {
+    pub config: UiConfig,
+    pub zoom: f64,
+}
+
+impl Ui {
+    pub fn new(config: UiConfig) -> Self {
+        Self { config, zoom: 1.0 }
+    }
+}
+
+pub trait UiActions {
+    fn ui_zoom(&mut self, delta: f64) -> Result<()>;
+    fn ui_zoom_reset(&mut self);
+}
+
+impl UiActions for State {
+    fn ui_zoom(&mut self, delta: f64) -> Result<()> {
+        // Rounded, so repeated steps do not accumulate floating point drift.
+        let zoom = (((self.ui.zoom + delta) * 10.0).round() / 10.0).clamp(MIN_ZOOM, MAX_ZOOM);
+
+        if (zoom - self.ui.zoom).abs() < f64::EPSILON {
+            return Err(UiError::ZoomLimit { zoom }.into());
+        }
+
+        self.ui.zoom = zoom;
+        Ok(())
+    }
+
+    fn ui_zoom_reset(&mut self) {
+        self.ui.zoom = 1.0;
+    }
+}
+
+pub fn ui_commands() -> CommandMap {
+    let mut commands = CommandMap::new();
+
+    commands.insert_command(
+        "ui-zoom-in",
+        "Zoom In",
+        "Scale up fonts, paddings and borders",
+        |state: &mut State| {
+            state.ui_zoom(ZOOM_STEP)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_command(
+        "ui-zoom-out",
+        "Zoom Out",
+        "Scale down fonts, paddings and borders",
+        |state: &mut State| {
+            state.ui_zoom(-ZOOM_STEP)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_command(
+        "ui-zoom-reset",
+        "Reset Zoom",
+        "Reset UI scale to configured sizes",
+        |state: &mut State| {
+            state.ui_zoom_reset();
+            Ok(Task::none())
+        },
+    );
+
+    commands
+}
@@ -2,36 +2,61 @@ use anyhow::Result;
 use iced::Task;
 use thiserror::Error;
 
+use crate::config::{edit_config, edit_table};
 use crate::state::{
     State,
-    command::CommandMap,
+    command::{CommandActions, CommandMap},
     mode::{ModeActions, ViewMode},
+    notification::NotificationActions,
+    template::TilingTemplate,
+    tiling::TilePlacement,
 };
 
 #[derive(Error, Debug)]
 pub enum ViewError {
     #[error("Default target for view action is not found")]
     NoTarget,
+    #[error("Configuration was not loaded from a file")]
+    NoConfigFile,
 }
 
 pub trait ViewActions {
-    fn view_expand_rows(&mut self) -> Result<()>;
-    fn view_shrink_rows(&mut self) -> Result<()>;
-    fn view_expand_columns(&mut self) -> Result<()>;
-    fn view_shrink_columns(&mut self) -> Result<()>;
-    fn view_scroll_down(&mut self) -> Result<()>;
-    fn view_scroll_up(&mut self) -> Result<()>;
+    fn view_expand_rows(&mut self, count: usize) -> Result<()>;
+    fn view_shrink_rows(&mut self, count: usize) -> Result<()>;
+    fn view_expand_columns(&mut self, count: usize) -> Result<()>;
+    fn view_shrink_columns(&mut self, count: usize) -> Result<()>;
+    fn view_scroll_down(&mut self, count: usize) -> Result<()>;
+    fn view_scroll_up(&mut self, count: usize) -> Result<()>;
     fn view_toggle_fullscreen(&mut self) -> Result<()>;
+    fn view_resize_column(&mut self, delta: i32) -> Result<()>;
+    fn view_resize_row(&mut self, delta: i32) -> Result<()>;
+    fn view_equalize(&mut self) -> Result<()>;
+    fn view_save_layout(&mut self) -> Result<()>;
+}
+
+// Row and column of focused tile in current view.
+fn focused_tile(state: &mut State) -> Result<TilePlacement> {
+    let Some(ViewMode::Workspace { id: Some(id) }) = state.current_view_mode() else {
+        return Err(ViewError::NoTarget.into());
+    };
+
+    let ids = &state.screen().workspace_ids;
+    let index = ids
+        .iter()
+        .position(|&workspace_id| workspace_id == id)
+        .ok_or(ViewError::NoTarget)?;
+
+    Ok(state.screen().tiling.layout(ids.len(), Some(index))[index])
 }
 
 impl ViewActions for State
 where
     State: ModeActions,
 {
-    fn view_expand_rows(&mut self) -> Result<()> {
+    fn view_expand_rows(&mut self, count: usize) -> Result<()> {
         match self.current_view_mode() {
             Some(ViewMode::Workspace { .. }) => {
-                self.screen.tiling.max_expanded_rows += 1;
+                self.screen_mut().tiling.max_expanded_rows += count;
             }
             _ => return Err(ViewError::NoTarget.into()),
         }
@@ -39,12 +64,12 @@ where
         Ok(())
     }
 
-    fn view_shrink_rows(&mut self) -> Result<()> {
+    // Shrinking stops at a single row.
+    fn view_shrink_rows(&mut self, count: usize) -> Result<()> {
         match self.current_view_mode() {
             Some(ViewMode::Workspace { .. }) => {
-                if self.screen.tiling.max_expanded_rows > 1 {
-                    self.screen.tiling.max_expanded_rows -= 1;
-                }
+                let tiling = &mut self.screen_mut().tiling;
+                tiling.max_expanded_rows = tiling.max_expanded_rows.saturating_sub(count).max(1);
             }
             _ => return Err(ViewError::NoTarget.into()),
         }
@@ -52,10 +77,10 @@ where
         Ok(())
     }
 
-    fn view_expand_columns(&mut self) -> Result<()> {
+    fn view_expand_columns(&mut self, count: usize) -> Result<()> {
         match self.current_view_mode() {
             Some(ViewMode::Workspace { .. }) => {
-                self.screen.tiling.max_columns += 1;
+                self.screen_mut().tiling.max_columns += count;
             }
             _ => return Err(ViewError::NoTarget.into()),
         }
@@ -63,12 +88,12 @@ where
         Ok(())
     }
 
-    fn view_shrink_columns(&mut self) -> Result<()> {
+    // Shrinking stops at a single column.
+    fn view_shrink_columns(&mut self, count: usize) -> Result<()> {
         match self.current_view_mode() {
             Some(ViewMode::Workspace { .. }) => {
-                if self.screen.tiling.max_columns > 1 {
-                    self.screen.tiling.max_columns -= 1;
-                }
+                let tiling = &mut self.screen_mut().tiling;
+                tiling.max_columns = tiling.max_columns.saturating_sub(count).max(1);
             }
             _ => return Err(ViewError::NoTarget.into()),
         }
@@ -76,34 +101,31 @@ where
         Ok(())
     }
 
-    fn view_scroll_down(&mut self) -> Result<()> {
-        match self.current_view_mode() {
-            Some(ViewMode::Workspace { .. }) => {
-                let max_row_count = self
-                    .screen
-                    .workspace_ids
-                    .len()
-                    .div_ceil(0.max(self.screen.tiling.max_columns));
-
-                if max_row_count > self.screen.tiling.max_expanded_rows
-                    && self.screen.tiling.top_expanded_row_index
-                        < max_row_count - self.screen.tiling.max_expanded_rows
-                {
-                    self.screen.tiling.top_expanded_row_index += 1;
-                }
-            }
-            _ => return Err(ViewError::NoTarget.into()),
+    // Scrolling needs no focused tile, so it works in any mode, e.g. from the wheel.
+    // It stops at the last row.
+    fn view_scroll_down(&mut self, count: usize) -> Result<()> {
+        let items = self.screen().workspace_ids.len();
+        for _ in 0..count {
+            self.screen_mut().tiling.scroll_down(items);
         }
 
         Ok(())
     }
 
-    fn view_scroll_up(self: &mut State) -> Result<()> {
+    // Scrolling stops at the first row.
+    fn view_scroll_up(self: &mut State, count: usize) -> Result<()> {
+        let items = self.screen().workspace_ids.len();
+        for _ in 0..count {
+            self.screen_mut().tiling.scroll_up(items);
+        }
+
+        Ok(())
+    }
+
+    fn view_toggle_fullscreen(&mut self) -> Result<()> {
         match self.current_view_mode() {
             Some(ViewMode::Workspace { .. }) => {
-                if self.screen.tiling.top_expanded_row_index > 0 {
-                    self.screen.tiling.top_expanded_row_index -= 1;
-                }
+                self.screen_mut().tiling.fullscreen = !self.screen().tiling.fullscreen;
             }
             _ => return Err(ViewError::NoTarget.into()),
         }
@@ -111,77 +133,123 @@ where
         Ok(())
     }
 
-    fn view_toggle_fullscreen(&mut self) -> Result<()> {
+    fn view_resize_column(&mut self, delta: i32) -> Result<()> {
+        let tile = focused_tile(self)?;
+        self.screen_mut().tiling.resize_column(tile.column, delta);
+
+        Ok(())
+    }
+
+    fn view_resize_row(&mut self, delta: i32) -> Result<()> {
+        let tile = focused_tile(self)?;
+        self.screen_mut().tiling.resize_row(tile.row, delta);
+
+        Ok(())
+    }
+
+    fn view_equalize(&mut self) -> Result<()> {
         match self.current_view_mode() {
-            Some(ViewMode::Workspace { .. }) => {
-                self.screen.tiling.fullscreen = !self.screen.tiling.fullscreen;
-            }
+            Some(ViewMode::Workspace { .. }) => self.screen_mut().tiling.equalize(),
             _ => return Err(ViewError::NoTarget.into()),
         }
 
         Ok(())
     }
+
+    // Layout of current screen is written to `[tiling]` and given to new screens.
+    fn view_save_layout(&mut self) -> Result<()> {
+        let path = self.config_path.as_ref().ok_or(ViewError::NoConfigFile)?;
+        let tiling = &self.screen().tiling;
+        let weights = |weights: &[u16]| {
+            weights
+                .iter()
+                .map(|&weight| i64::from(weight))
+                .collect::<toml_edit::Array>()
+        };
+
+        edit_config(path, |document| {
+            let table = edit_table(document.as_table_mut(), "tiling")?;
+            table.insert("max-columns", toml_edit::value(tiling.max_columns as i64));
+            table.insert(
+                "max-expanded-rows",
+                toml_edit::value(tiling.max_expanded_rows as i64),
+            );
+            table.insert(
+                "column-weights",
+                toml_edit::value(weights(&tiling.column_weights)),
+            );
+            table.insert(
+                "row-weights",
+                toml_edit::value(weights(&tiling.row_weights)),
+            );
+            Ok(())
+        })?;
+
+        self.layout = TilingTemplate::from(&self.screen().tiling);
+        self.push_info("Saved layout");
+        Ok(())
+    }
 }
 
 pub fn view_commands() -> CommandMap {
     let mut commands = CommandMap::new();
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "view-expand-rows",
         "Expand View Rows",
         "Expand visible rows in current view",
         |state: &mut State| {
-            state.view_expand_rows()?;
+            state.view_expand_rows(state.command_count())?;
             Ok(Task::none())
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "view-shrink-rows",
         "Shrink View Rows",
         "Shrink visible rows in current view",
         |state: &mut State| {
-            state.view_shrink_rows()?;
+            state.view_shrink_rows(state.command_count())?;
             Ok(Task::none())
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "view-expand-columns",
         "Expand View Columns",
         "Expand visible columns in current view",
         |state: &mut State| {
-            state.view_expand_columns()?;
+            state.view_expand_columns(state.command_count())?;
             Ok(Task::none())
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "view-shrink-columns",
         "Shrink View Columns",
         "Shrink visible columns in current view",
         |state: &mut State| {
-            state.view_shrink_columns()?;
+            state.view_shrink_columns(state.command_count())?;
             Ok(Task::none())
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "view-scroll-down",
         "Scroll Down View",
         "Scroll down expanded rows in current view",
         |state: &mut State| {
-            state.view_scroll_down()?;
+            state.view_scroll_down(state.command_count())?;
             Ok(Task::none())
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "view-scroll-up",
         "Scroll Up View",
         "Scroll up expanded rows in current view",
         |state: &mut State| {
-            state.view_scroll_up()?;
+            state.view_scroll_up(state.command_count())?;
             Ok(Task::none())
         },
     );
@@ -196,5 +264,239 @@ pub fn view_commands() -> CommandMap {
         },
     );
 
+    commands.insert_counted_command(
+        "view-grow-column",
+        "Grow Column",
+        "Widen column of currently selected item",
+        |state: &mut State| {
+            state.view_resize_column(state.command_count() as i32)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_counted_command(
+        "view-shrink-column",
+        "Shrink Column",
+        "Narrow column of currently selected item",
+        |state: &mut State| {
+            state.view_resize_column(-(state.command_count() as i32))?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_counted_command(
+        "view-grow-row",
+        "Grow Row",
+        "Heighten row of currently selected item",
+        |state: &mut State| {
+            state.view_resize_row(state.command_count() as i32)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_counted_command(
+        "view-shrink-row",
+        "Shrink Row",
+        "Lower row of currently selected item",
+        |state: &mut State| {
+            state.view_resize_row(-(state.command_count() as i32))?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_command(
+        "view-save-layout",
+        "Save Layout",
+        "Write layout of current screen to configuration file",
+        |state: &mut State| {
+            state.view_save_layout()?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_command(
+        "view-equalize",
+        "Equalize Tiles",
+        "Reset proportions of all columns and rows",
+        |state: &mut State| {
+            state.view_equalize()?;
+            Ok(Task::none())
+        },
+    );
+
     commands
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::state::tiling::DEFAULT_WEIGHT;
+    use crate::testing::Harness;
+
+    fn in_view_mode(workspaces: usize) -> Harness {
+        let mut harness = Harness::new();
+
+        for _ in 0..workspaces {
+            harness.run("workspace-create");
+        }
+        harness.run("view-mode");
+        harness
+    }
+
+    #[test]
+    fn expand_and_shrink_rows() {
+        let mut harness = in_view_mode(0);
+
+        harness.run("view-expand-rows");
+        assert_eq!(harness.tiling().max_expanded_rows, 3);
+
+        harness
+            .run("view-shrink-rows")
+            .run("view-shrink-rows")
+            .run("view-shrink-rows");
+        assert_eq!(harness.tiling().max_expanded_rows, 1);
+    }
+
+    #[test]
+    fn expand_and_shrink_columns() {
+        let mut harness = in_view_mode(0);
+
+        harness.run("view-expand-columns");
+        assert_eq!(harness.tiling().max_columns, 4);
+
+        for _ in 0..4 {
+            harness.run("view-shrink-columns");
+        }
+        assert_eq!(harness.tiling().max_columns, 1);
+    }
+
+    #[test]
+    fn scroll_stays_within_rows() {
+        // Three columns and two expanded rows, so 10 workspaces make two rows off the view.
+        // Creating a workspace reveals it, so the view starts scrolled to the last row.
+        let mut harness = in_view_mode(10);
+        assert_eq!(harness.tiling().top_expanded_row_index, 2);
+
+        for _ in 0..3 {
+            harness.run("view-scroll-up");
+        }
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+
+        harness.run("view-scroll-down");
+        assert_eq!(harness.tiling().top_expanded_row_index, 1);
+
+        harness.run("view-scroll-down").run("view-scroll-down");
+        assert_eq!(harness.tiling().top_expanded_row_index, 2);
+    }
+
+    #[test]
+    fn scroll_without_hidden_rows_is_noop() {
+        let mut harness = in_view_mode(6);
+
+        harness.run("view-scroll-down");
+
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+    }
+
+    #[test]
+    fn toggle_fullscreen() {
+        let mut harness = in_view_mode(1);
+
+        harness.run("view-toggle-fullscreen");
+        assert!(harness.tiling().fullscreen);
+
+        harness.run("view-toggle-fullscreen");
+        assert!(!harness.tiling().fullscreen);
+    }
+
+    #[test]
+    fn resize_focused_column_and_row() {
+        // Fifth workspace is in the middle column of the second row.
+        let mut harness = in_view_mode(5);
+
+        harness
+            .run("view-grow-column")
+            .run("view-grow-column")
+            .run("view-shrink-row");
+
+        let tiling = harness.tiling();
+        assert_eq!(tiling.column_weight(1), DEFAULT_WEIGHT + 2);
+        assert_eq!(tiling.column_weight(0), DEFAULT_WEIGHT);
+        assert_eq!(tiling.row_weight(1), DEFAULT_WEIGHT - 1);
+        assert_eq!(tiling.row_weight(0), DEFAULT_WEIGHT);
+
+        harness.run("view-shrink-column").run("view-grow-row");
+
+        assert_eq!(harness.tiling().column_weight(1), DEFAULT_WEIGHT + 1);
+        assert_eq!(harness.tiling().row_weight(1), DEFAULT_WEIGHT);
+    }
+
+    #[test]
+    fn resize_requires_focused_tile() {
+        let mut harness = in_view_mode(0);
+
+        harness.run("view-grow-column").run("view-grow-row");
+
+        assert_eq!(harness.errors(), 2);
+    }
+
+    #[test]
+    fn equalize_resets_proportions() {
+        let mut harness = in_view_mode(2);
+
+        harness
+            .run("view-grow-column")
+            .run("view-grow-row")
+            .run("view-equalize");
+
+        assert!(harness.tiling().column_weights.is_empty());
+        assert!(harness.tiling().row_weights.is_empty());
+    }
+
+    #[test]
+    fn view_commands_require_view_mode() {
+        let mut harness = Harness::new();
+
+        for handle in [
+            "view-expand-rows",
+            "view-shrink-rows",
+            "view-expand-columns",
+            "view-shrink-columns",
+            "view-toggle-fullscreen",
+            "view-equalize",
+        ] {
+            harness.run(handle);
+        }
+
+        assert_eq!(harness.errors(), 6);
+        assert_eq!(harness.tiling().max_expanded_rows, 2);
+        assert!(!harness.tiling().fullscreen);
+    }
+
+    #[test]
+    fn saved_layout_is_given_to_new_screens() {
+        let (mut harness, config) = Harness::with_config_file("# keep me\n");
+        harness.press_all(&["c", "cmd-v", "l", "l", "e", "s"]);
+
+        let content = config.read();
+        assert!(content.contains("# keep me"));
+        assert_eq!(harness.errors(), 0);
+
+        let saved = harness.tiling();
+        let restored = Harness::with_extra_config(&content);
+        assert_eq!(restored.tiling().column_weights, saved.column_weights);
+        assert_eq!(restored.tiling().max_expanded_rows, 3);
+
+        harness.run("screen-create");
+        assert_eq!(harness.app.focused_screen, 1);
+        assert_eq!(harness.tiling().column_weight(0), DEFAULT_WEIGHT + 2);
+    }
+
+    #[test]
+    fn saving_layout_requires_config_file() {
+        let mut harness = Harness::new();
+
+        harness.run("view-save-layout");
+
+        assert_eq!(harness.errors(), 1);
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use iced::Task;
+use std::path::PathBuf;
 use thiserror::Error;
 
 use crate::state::{
-    State, Uid, command::CommandMap, create_uid, mode::ModeActions, tiling::Tiling,
+    State, Uid, command::{CommandActions, CommandMap}, create_uid, mode::ModeActions,
+    notification::NotificationActions, screen::switch_screen, tiling::Tiling,
 };
 
 #[derive(Error, Debug)]
@@ -12,10 +14,14 @@ pub enum WorkspaceError {
     NotFound { id: Uid },
     #[error("Default target for workspace action is not found")]
     NoTarget,
+    #[error("Workspace root {path:?} is not a directory")]
+    NotADirectory { path: PathBuf },
 }
 
 pub struct Workspace {
     pub id: Uid,
+    pub name: String,
+    pub root: Option<PathBuf>,
     pub activity_ids: Vec<Uid>,
     pub tiling: Tiling,
 }
@@ -24,88 +30,206 @@ impl Default for Workspace {
     fn default() -> Self {
         Self {
             id: create_uid(),
+            name: String::new(),
+            root: None,
             activity_ids: Vec::new(),
             tiling: Tiling::default(),
         }
     }
 }
 
+// Adds workspace to the screen and focuses it.
+pub fn insert_workspace(state: &mut State, workspace: Workspace) -> Uid {
+    let workspace_id = workspace.id;
+
+    state.screen_mut().workspace_ids.push(workspace_id);
+    state.workspaces.insert(workspace_id, workspace);
+    state.push_workspace_mode(Some(workspace_id));
+    reveal_workspace(state, workspace_id);
+
+    workspace_id
+}
+
+// Scrolls the view so focused workspace is in expanded rows.
+fn reveal_workspace(state: &mut State, workspace_id: Uid) {
+    let items = state.screen().workspace_ids.len();
+
+    if let Some(index) = state.screen().workspace_ids.iter().position(|&id| id == workspace_id) {
+        state.screen_mut().tiling.reveal(index, items);
+    }
+}
+
+// Removes workspace from focused screen and focuses the one before it.
+pub fn detach_workspace(state: &mut State, workspace_id: Uid) -> Result<()> {
+    let workspace_ids = &mut state.screen_mut().workspace_ids;
+    let index = workspace_ids
+        .iter()
+        .position(|&id| id == workspace_id)
+        .ok_or(WorkspaceError::NoTarget)?;
+    workspace_ids.remove(index);
+
+    let previous_id = workspace_ids.get(index.saturating_sub(1)).copied();
+    state.update_workspace_mode(previous_id);
+    if let Some(previous_id) = previous_id {
+        reveal_workspace(state, previous_id);
+    }
+
+    Ok(())
+}
+
 pub trait WorkspaceActions {
     fn create_workspace(&mut self) -> Uid;
+    fn open_workspace(&mut self, root: PathBuf) -> Result<Uid>;
+    fn open_workspace_with(&mut self, root: PathBuf, workspace: Workspace) -> Result<Uid>;
     fn delete_workspace(&mut self) -> Result<()>;
     fn next_workspace(&mut self);
     fn previous_workspace(&mut self);
+    fn focus_workspace(&mut self, workspace_id: Uid) -> Result<()>;
+    fn move_workspace(&mut self, offset: isize) -> Result<()>;
 }
 
 impl WorkspaceActions for State {
+    // Numbers are shared by all screens, the lowest one not in use is taken.
     fn create_workspace(&mut self) -> Uid {
-        let new_workspace_id = create_uid();
-        self.screen.workspace_ids.push(new_workspace_id);
-        self.workspaces
-            .insert(new_workspace_id, Workspace::default());
+        let name = (1..)
+            .map(|number| format!("workspace {number}"))
+            .find(|name| !self.workspaces.values().any(|w| w.name == *name))
+            .expect("workspace numbers are unbounded");
 
-        self.push_workspace_mode(Some(new_workspace_id));
+        insert_workspace(
+            self,
+            Workspace {
+                name,
+                ..Workspace::default()
+            },
+        )
+    }
 
-        new_workspace_id
+    fn open_workspace(&mut self, root: PathBuf) -> Result<Uid> {
+        self.open_workspace_with(root, Workspace::default())
     }
 
-    fn delete_workspace(&mut self) -> Result<()> {
-        let mut previous_index = 0;
+    // Workspace without a name is named after its root directory.
+    fn open_workspace_with(&mut self, root: PathBuf, mut workspace: Workspace) -> Result<Uid> {
+        if !root.is_dir() {
+            return Err(WorkspaceError::NotADirectory { path: root }.into());
+        }
 
-        if let Some(workspace_id) = self.current_workspace_id() {
-            if let Some(index) = self
-                .screen
-                .workspace_ids
-                .iter()
-                .position(|&x| x == workspace_id)
-            {
-                self.screen.workspace_ids.remove(index);
-                self.workspaces.remove(&workspace_id);
-
-                previous_index = if index > 0 { index - 1 } else { 0 };
-            } else {
-                return Err(WorkspaceError::NoTarget.into());
-            }
+        if workspace.name.is_empty() {
+            workspace.name = root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| root.display().to_string());
         }
+        workspace.root = Some(root);
 
-        if self.screen.workspace_ids.is_empty() {
-            self.update_workspace_mode(None);
-        } else {
-            self.update_workspace_mode(self.screen.workspace_ids.get(previous_index).copied());
+        Ok(insert_workspace(self, workspace))
+    }
+
+    fn delete_workspace(&mut self) -> Result<()> {
+        match self.current_workspace_id() {
+            Some(workspace_id) => {
+                detach_workspace(self, workspace_id)?;
+                if let Some(workspace) = self.workspaces.remove(&workspace_id) {
+                    for activity_id in &workspace.activity_ids {
+                        self.activities.remove(activity_id);
+                    }
+                }
+                self.push_info(format!("Workspace {} deleted", workspace_id));
+            }
+            None => {
+                let workspace_id = self.screen().workspace_ids.first().copied();
+                self.update_workspace_mode(workspace_id);
+            }
         }
 
         Ok(())
     }
 
     fn next_workspace(&mut self) {
-        let next_workspace_id = if let Some(current_workspace_id) = self.current_workspace_id() {
-            self.screen
-                .workspace_ids
+        let workspace_ids = &self.screen().workspace_ids;
+        // Current workspace may be missing from the screen, e.g. after a screen switch.
+        let index = self.current_workspace_id().and_then(|id| {
+            workspace_ids
                 .iter()
-                .cycle()
-                .skip_while(|workspace_id| **workspace_id != current_workspace_id).nth(1)
-                .copied()
-        } else {
-            self.screen.workspace_ids.first().copied()
+                .position(|&workspace_id| workspace_id == id)
+        });
+        let next_workspace_id = match index {
+            Some(index) => workspace_ids
+                .get((index + 1) % workspace_ids.len())
+                .copied(),
+            None => workspace_ids.first().copied(),
         };
 
         self.update_workspace_mode(next_workspace_id);
+        if let Some(workspace_id) = next_workspace_id {
+            reveal_workspace(self, workspace_id);
+        }
     }
 
     fn previous_workspace(&mut self) {
-        let next_workspace_id = if let Some(current_workspace_id) = self.current_workspace_id() {
-            self.screen
-                .workspace_ids
+        let workspace_ids = &self.screen().workspace_ids;
+        let index = self.current_workspace_id().and_then(|id| {
+            workspace_ids
                 .iter()
-                .rev()
-                .cycle()
-                .skip_while(|workspace_id| **workspace_id != current_workspace_id).nth(1)
-                .copied()
-        } else {
-            self.screen.workspace_ids.last().copied()
+                .position(|&workspace_id| workspace_id == id)
+        });
+        let next_workspace_id = match index {
+            Some(index) => workspace_ids
+                .get((index + workspace_ids.len() - 1) % workspace_ids.len())
+                .copied(),
+            None => workspace_ids.last().copied(),
         };
 
         self.update_workspace_mode(next_workspace_id);
+        if let Some(workspace_id) = next_workspace_id {
+            reveal_workspace(self, workspace_id);
+        }
+    }
+
+    // Focuses workspace keeping current view mode, e.g. when its tile is clicked.
+    fn focus_workspace(&mut self, workspace_id: Uid) -> Result<()> {
+        if !self.workspaces.contains_key(&workspace_id) {
+            return Err(WorkspaceError::NotFound { id: workspace_id }.into());
+        }
+
+        // Workspace may be shown in another window.
+        if let Some(index) = self
+            .screens
+            .iter()
+            .position(|screen| screen.workspace_ids.contains(&workspace_id))
+        {
+            let _ = switch_screen(self, index);
+        }
+
+        if self.current_view_mode().is_some() {
+            self.update_view_workspace_mode(Some(workspace_id));
+        } else {
+            self.update_workspace_mode(Some(workspace_id));
+        }
+        reveal_workspace(self, workspace_id);
+
+        Ok(())
+    }
+
+    // Moves focused workspace `offset` positions, stopping at either end, keeping it focused.
+    fn move_workspace(&mut self, offset: isize) -> Result<()> {
+        let workspace_id = self.current_workspace_id().ok_or(WorkspaceError::NoTarget)?;
+        let ids = &mut self.screen_mut().workspace_ids;
+
+        let index = ids
+            .iter()
+            .position(|&id| id == workspace_id)
+            .ok_or(WorkspaceError::NoTarget)?;
+        let target = index.saturating_add_signed(offset).min(ids.len() - 1);
+
+        if target != index {
+            let id = ids.remove(index);
+            ids.insert(target, id);
+            reveal_workspace(self, workspace_id);
+        }
+
+        Ok(())
     }
 }
 
@@ -132,24 +256,179 @@ pub fn workspace_commands() -> CommandMap {
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "workspace-next",
         "Next Workspace",
         "Focus on next workspace",
         |state: &mut State| {
-            state.next_workspace();
+            for _ in 0..state.command_count() {
+                state.next_workspace();
+            }
             Ok(Task::none())
         },
     );
 
-    commands.insert_command(
+    commands.insert_counted_command(
         "workspace-previous",
         "Previous Workspace",
         "Focus on previous workspace",
         |state: &mut State| {
-            state.previous_workspace();
+            for _ in 0..state.command_count() {
+                state.previous_workspace();
+            }
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_counted_command(
+        "workspace-move-next",
+        "Move Workspace Forward",
+        "Swap current workspace with the next one",
+        |state: &mut State| {
+            state.move_workspace(state.command_count() as isize)?;
+            Ok(Task::none())
+        },
+    );
+
+    commands.insert_counted_command(
+        "workspace-move-previous",
+        "Move Workspace Backward",
+        "Swap current workspace with the previous one",
+        |state: &mut State| {
+            state.move_workspace(-(state.command_count() as isize))?;
             Ok(Task::none())
         },
     );
     commands
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::state::mode::Mode;
+    use crate::testing::Harness;
+
+    #[test]
+    fn create_focuses_new_workspace() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("workspace-create");
+
+        let ids = harness.workspace_ids().to_vec();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(harness.focused(), Some(ids[1]));
+        assert_eq!(harness.app.workspaces[&ids[1]].name, "workspace 2");
+        assert!(harness.mode_history().is_empty());
+    }
+
+    #[test]
+    fn delete_focuses_previous_workspace() {
+        let mut harness = Harness::new();
+
+        harness
+            .run("workspace-create")
+            .run("workspace-create")
+            .run("workspace-create")
+            .run("workspace-previous")
+            .run("workspace-delete");
+
+        let ids = harness.workspace_ids().to_vec();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(harness.app.workspaces.len(), 2);
+        assert_eq!(harness.focused(), Some(ids[0]));
+    }
+
+    #[test]
+    fn delete_last_workspace_clears_focus() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("workspace-delete");
+
+        assert!(harness.workspace_ids().is_empty());
+        assert_eq!(harness.mode(), &Mode::Workspace { id: None });
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn next_wraps_around() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("workspace-create");
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.run("workspace-next");
+        assert_eq!(harness.focused(), Some(ids[0]));
+
+        harness.run("workspace-next");
+        assert_eq!(harness.focused(), Some(ids[1]));
+    }
+
+    #[test]
+    fn previous_wraps_around() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("workspace-create");
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.run("workspace-previous");
+        assert_eq!(harness.focused(), Some(ids[0]));
+
+        harness.run("workspace-previous");
+        assert_eq!(harness.focused(), Some(ids[1]));
+    }
+
+    #[test]
+    fn focus_change_scrolls_view() {
+        let mut harness = Harness::new();
+
+        for _ in 0..10 {
+            harness.run("workspace-create");
+        }
+        assert_eq!(harness.tiling().top_expanded_row_index, 2);
+
+        harness.run("workspace-next");
+        assert_eq!(harness.tiling().top_expanded_row_index, 0);
+
+        harness.run("workspace-previous");
+        assert_eq!(harness.tiling().top_expanded_row_index, 2);
+    }
+
+    #[test]
+    fn move_swaps_with_neighbour() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("workspace-create");
+        let ids = harness.workspace_ids().to_vec();
+
+        harness.run("workspace-move-previous");
+        assert_eq!(harness.workspace_ids(), &[ids[1], ids[0]]);
+        assert_eq!(harness.focused(), Some(ids[1]));
+
+        harness.run("workspace-move-previous");
+        assert_eq!(harness.workspace_ids(), &[ids[1], ids[0]]);
+
+        harness.run("workspace-move-next");
+        assert_eq!(harness.workspace_ids(), ids.as_slice());
+        assert_eq!(harness.errors(), 0);
+    }
+
+    #[test]
+    fn move_without_focus_fails() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-move-next");
+
+        assert_eq!(harness.errors(), 1);
+    }
+
+    #[test]
+    fn next_without_focus_focuses_first() {
+        let mut harness = Harness::new();
+
+        harness.run("workspace-create").run("workspace-create");
+        let first = harness.workspace_ids()[0];
+        harness.app.mode = Mode::Workspace { id: None };
+
+        harness.run("workspace-next");
+        assert_eq!(harness.focused(), Some(first));
+    }
+}
@@ -0,0 +1,259 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+use iced::mouse::ScrollDelta;
+use iced::window;
+use std::path::PathBuf;
+
+use crate::app::App;
+use crate::cli::LaunchArgs;
+use crate::config::{Config, parse_keybind};
+use crate::message::Message;
+use crate::state::mode::{Mode, ModeActions};
+use crate::state::tiling::Tiling;
+use crate::state::{State, Uid, create_uid};
+
+// Keymaps mirror the default `config.toml`, without anything that depends on the host.
+pub const TEST_CONFIG: &str = r#"
+[keymaps.global]
+workspace-mode = "cmd-w"
+view-mode = "cmd-v"
+pop-mode = "esc"
+pop-to-root = "shift-esc"
+notifications-history = "cmd-m"
+notifications-dismiss = "cmd-d"
+notifications-clear = "cmd-shift-d"
+log-viewer = "cmd-l"
+theme-cycle = "cmd-t"
+ui-zoom-in = "cmd-="
+ui-zoom-out = "cmd-minus"
+ui-zoom-reset = "cmd-0"
+screen-create = "cmd-shift-o"
+screen-focus-next = "cmd-o"
+macro-record-start = "cmd-r"
+macro-record-stop = "cmd-shift-r"
+macro-replay = "cmd-e"
+macro-save = "cmd-s"
+keymap-check = "cmd-k"
+binding-editor = "cmd-b"
+workspace-switch = "alt-n"
+overview = "cmd-shift-w"
+
+[keymaps.workspace-mode]
+workspace-create = "c"
+workspace-delete = "shift-c"
+workspace-next = "n"
+workspace-previous = "shift-n"
+workspace-move-next = "m"
+workspace-move-previous = "shift-m"
+workspace-move-to-next-screen = "o"
+workspace-create-from-template = "t"
+repeat-last-command = "."
+view-mode-replace = "v"
+
+[keymaps.view-mode]
+view-expand-rows = "e"
+view-shrink-rows = "shift-e"
+view-expand-columns = "v"
+view-shrink-columns = "shift-v"
+view-scroll-down = "n"
+view-scroll-up = "shift-n"
+view-toggle-fullscreen = "."
+view-grow-column = "l"
+view-shrink-column = "h"
+view-grow-row = "k"
+view-shrink-row = "j"
+view-equalize = "="
+view-save-layout = "s"
+workspace-mode-replace = "w"
+
+[keymaps.picker-mode]
+picker-next = "down"
+picker-previous = "up"
+picker-confirm = "enter"
+workspace-switch-confirm = "release-alt"
+
+[keymaps.binding-mode]
+binding-editor-next = "down"
+binding-editor-previous = "up"
+binding-editor-keymap = "m"
+binding-editor-rebind = "enter"
+binding-editor-confirm = "y"
+
+[keymaps.overview-mode]
+extends = ["overview-arrows", "global"]
+overview-left = "h"
+overview-right = "l"
+overview-up = "k"
+overview-down = "j"
+overview-filter = "/"
+overview-confirm = "enter"
+
+[keymaps.overview-arrows]
+extends = []
+overview-left = "left"
+overview-right = "right"
+overview-up = "up"
+overview-down = "down"
+
+[theme]
+name = "Dark"
+"#;
+
+// Named key of a modifier and the modifiers still held once it is released.
+fn released_modifier(modifiers: Modifiers) -> (Key, Modifiers) {
+    let (named, modifier) = [
+        (Named::Alt, Modifiers::ALT),
+        (Named::Control, Modifiers::CTRL),
+        (Named::Shift, Modifiers::SHIFT),
+        (Named::Super, Modifiers::LOGO),
+    ]
+    .into_iter()
+    .find(|(_, modifier)| modifiers.contains(*modifier))
+    .expect("keybind has a key or a modifier");
+
+    (Key::Named(named), modifiers.difference(modifier))
+}
+
+// State with all application commands, built from `TEST_CONFIG`.
+pub fn test_state() -> State {
+    Harness::new().app
+}
+
+// Configuration file in the temporary directory, removed when dropped.
+pub struct TempConfig(pub PathBuf);
+
+impl TempConfig {
+    pub fn new(content: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("cinnabar-{}.toml", create_uid()));
+        std::fs::write(&path, content).unwrap();
+        Self(path)
+    }
+
+    pub fn read(&self) -> String {
+        std::fs::read_to_string(&self.0).unwrap()
+    }
+}
+
+impl Drop for TempConfig {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+// Drives the application the same way iced does, one message at a time.
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with_config(TEST_CONFIG)
+    }
+
+    pub fn with_config(config: &str) -> Self {
+        let (app, _task) = App::new(Config::parse(config), LaunchArgs::default());
+
+        Self { app }
+    }
+
+    // Test configuration followed by tables specific to a test.
+    pub fn with_extra_config(extra: &str) -> Self {
+        Self::with_config(&format!("{TEST_CONFIG}\n{extra}"))
+    }
+
+    // Default harness with given number of workspaces, the last one focused.
+    pub fn with_workspaces(count: usize) -> Self {
+        let mut harness = Self::new();
+        for _ in 0..count {
+            harness.run("workspace-create");
+        }
+        harness
+    }
+
+    // Default harness saving changes into a configuration file with given content.
+    pub fn with_config_file(content: &str) -> (Self, TempConfig) {
+        let config = TempConfig::new(content);
+        let mut harness = Self::new();
+        harness.app.config_path = Some(config.0.clone());
+        (harness, config)
+    }
+
+    // Presses a keybind written the same way as in the config, e.g. `cmd-shift-d`.
+    pub fn press(&mut self, keybind: &str) -> &mut Self {
+        let (key, modifiers) = parse_keybind(keybind.split('-').collect());
+
+        let _ = self.app.update(Message::KeyPress { key, modifiers });
+        self
+    }
+
+    // Modifier released alone, e.g. `alt`, is reported by iced as its named key.
+    pub fn release(&mut self, keybind: &str) -> &mut Self {
+        let (key, modifiers) = match parse_keybind(keybind.split('-').collect()) {
+            (Key::Unidentified, modifiers) => released_modifier(modifiers),
+            keybind => keybind,
+        };
+
+        let _ = self.app.update(Message::KeyRelease { key, modifiers });
+        self
+    }
+
+    pub fn press_all(&mut self, keybinds: &[&str]) -> &mut Self {
+        for keybind in keybinds {
+            self.press(keybind);
+        }
+        self
+    }
+
+    pub fn run(&mut self, handle: &str) -> &mut Self {
+        let _ = self.app.update(Message::Command(handle.to_string()));
+        self
+    }
+
+    pub fn click(&mut self, workspace_id: Uid) -> &mut Self {
+        let _ = self.app.update(Message::TilePressed(workspace_id));
+        let _ = self.app.update(Message::TileReleased(workspace_id));
+        self
+    }
+
+    pub fn drag(&mut self, from: Uid, to: Uid) -> &mut Self {
+        let _ = self.app.update(Message::TilePressed(from));
+        let _ = self.app.update(Message::TileReleased(to));
+        self
+    }
+
+    // Scrolls over focused screen.
+    pub fn scroll(&mut self, delta: ScrollDelta) -> &mut Self {
+        let _ = self
+            .app
+            .update(Message::WheelScrolled(self.window(), delta));
+        self
+    }
+
+    pub fn mode(&self) -> &Mode {
+        &self.app.mode
+    }
+
+    pub fn mode_history(&self) -> &[Mode] {
+        &self.app.mode_history
+    }
+
+    pub fn focused(&self) -> Option<Uid> {
+        self.app.current_workspace_id()
+    }
+
+    // Window of focused screen.
+    pub fn window(&self) -> window::Id {
+        self.app.screen().window.expect("screen window is opened")
+    }
+
+    pub fn workspace_ids(&self) -> &[Uid] {
+        &self.app.screen().workspace_ids
+    }
+
+    pub fn tiling(&self) -> &Tiling {
+        &self.app.screen().tiling
+    }
+
+    pub fn errors(&self) -> usize {
+        self.app.notifications.error_count()
+    }
+}